@@ -1,10 +1,24 @@
-use chrono::TimeZone;
+use anyhow::anyhow;
 use dioxus::prelude::*;
 use rfd::FileDialog;
-use snap_coin::{core::transaction::TransactionId, crypto::Signature, to_snap};
-use std::fs;
+use snap_coin::{core::transaction::TransactionId, crypto::Hash};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
 
-use crate::{copy_box::CopyBox, GlobalContext, Route};
+use crate::{
+    annotations::{save_annotations, Annotation},
+    chain::{input_amount, ChainError},
+    copy_box::CopyBox,
+    receipt_document::{render_html, render_pdf},
+    receipt::{
+        EntryDetails, EntryReveal, EntryRole, ReceiptEntry, ReceiptPayload, SignedReceipt,
+        RECEIPT_VERSION,
+    },
+    sync::SyncState,
+    GlobalContext, Route,
+};
 
 #[component]
 pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
@@ -15,60 +29,316 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
     }
     let client = global().api_client.unwrap();
 
+    let sync = consume_context::<Signal<SyncState>>();
     let mut tx = use_signal(|| None);
-    let mut input_amounts = use_signal(|| None);
+    let mut input_amounts: Signal<Vec<Option<u64>>> = use_signal(Vec::new);
     let mut status = use_signal(|| "".to_string());
+    let mut warning = use_signal(|| "".to_string());
+    let mut retry = use_signal(|| 0u32);
 
     use_effect(move || {
+        retry();
         let client = client.clone();
         spawn(async move {
-            if let Err(e) = async move {
-                if let Some(tx_d) = client.get_transaction_and_info(&transaction).await? {
-                    tx.set(Some(tx_d.clone()));
-                    let mut amounts = vec![];
-                    for input in tx_d.transaction.inputs {
-                        let funder_tx = client
-                            .get_transaction(&input.transaction_id)
-                            .await?
-                            .expect("Could not find funder tx");
-                        amounts.push(funder_tx.outputs[input.output_index].amount)
+            status.set("".to_string());
+            let syncing = sync.peek().syncing;
+
+            let result = async {
+                let tx_d = client
+                    .get_transaction_and_info(&transaction)
+                    .await?
+                    .ok_or_else(|| ChainError::missing(&transaction))?;
+
+                // A missing funder only costs us its amount, the rest of the transaction can still be annotated
+                let mut amounts = vec![];
+                for input in &tx_d.transaction.inputs {
+                    match input_amount(&client, input).await {
+                        Ok(amount) => amounts.push(Some(amount)),
+                        Err(ChainError::ConnectionLost) => return Err(ChainError::ConnectionLost),
+                        Err(_) => amounts.push(None),
                     }
-                    input_amounts.set(Some(amounts));
-                } else {
-                    status.set("Could not find transaction".to_string());
                 }
 
-                Ok::<(), anyhow::Error>(())
+                Ok((tx_d, amounts))
             }
-            .await
-            {
-                status.set(e.to_string());
+            .await;
+
+            match result {
+                Ok((tx_d, amounts)) => {
+                    if amounts.contains(&None) {
+                        warning.set(
+                            "Some funding transactions could not be found, their amounts are unknown"
+                                .to_string(),
+                        );
+                    }
+                    input_amounts.set(amounts);
+                    tx.set(Some(tx_d));
+                }
+                Err(e) => status.set(e.or_syncing(syncing).to_string()),
             }
         });
     });
 
     if tx().is_none() {
-        return rsx! {};
+        if status().is_empty() {
+            return rsx! {};
+        }
+        return rsx! {
+            div {
+                class: "p-10 flex items-center gap-5",
+                p { class: "text-red-400! font-bold", "{status}" }
+                button {
+                    onclick: move |_| retry += 1,
+                    "Retry"
+                }
+            }
+        };
     }
+    let loaded_tx = tx;
     let tx = tx.unwrap();
 
-    let mut title = use_signal(|| "".to_string());
-    let mut description = use_signal(|| "".to_string());
+    // Pick up where the user left off the last time this transaction was annotated
+    let saved = global()
+        .annotations
+        .get(&transaction)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut title = use_signal(|| saved.title.clone());
+    let mut description = use_signal(|| saved.description.clone());
 
     let mut inputs: Signal<Vec<String>> = use_signal(|| {
-        tx.transaction
-            .inputs
-            .iter()
-            .map(|_| String::new())
-            .collect()
+        let mut notes = saved.funder_notes.clone();
+        notes.resize(tx.transaction.inputs.len(), String::new());
+        notes
     });
     let mut outputs: Signal<Vec<String>> = use_signal(|| {
-        tx.transaction
-            .outputs
+        let mut notes = saved.payee_notes.clone();
+        notes.resize(tx.transaction.outputs.len(), String::new());
+        notes
+    });
+
+    // Only a wallet that took part in the transaction can vouch for it
+    let mut parties: Vec<(String, Vec<EntryRole>)> = global()
+        .wallets
+        .iter()
+        .filter_map(|(name, private)| {
+            let roles = EntryRole::of(&private.to_public(), &tx.transaction);
+            (!roles.is_empty()).then(|| (name.clone(), roles))
+        })
+        .collect();
+    parties.sort_by(|a, b| a.0.cmp(&b.0));
+    let selected_wallet = global().selected_wallet;
+    let default_signer = if parties.iter().any(|(name, _)| *name == selected_wallet) {
+        Some(selected_wallet)
+    } else {
+        parties.first().map(|(name, _)| name.clone())
+    };
+    let mut signer = use_signal(|| default_signer);
+
+    // Which entries are hidden, and the salt each one is committed with
+    let mut hidden: Signal<HashSet<(EntryRole, usize)>> =
+        use_signal(|| saved.hidden.iter().copied().collect());
+    let salts: Signal<HashMap<(EntryRole, usize), Hash>> = use_signal(|| {
+        let saved_salts: HashMap<(EntryRole, usize), Hash> = saved
+            .salts
             .iter()
-            .map(|_| String::new())
+            .map(|(role, i, salt)| ((*role, *i), *salt))
+            .collect();
+        let funders = (0..tx.transaction.inputs.len()).map(|i| (EntryRole::Funder, i));
+        let payees = (0..tx.transaction.outputs.len()).map(|i| (EntryRole::Payee, i));
+        funders
+            .chain(payees)
+            .map(|key| {
+                let salt = saved_salts
+                    .get(&key)
+                    .copied()
+                    .unwrap_or_else(|| Hash::new_from_buf(rand::random()));
+                (key, salt)
+            })
             .collect()
     });
+    // Salts are kept from the first export with hidden entries on, the receipt can be out there
+    let mut salts_used = use_signal(|| !saved.salts.is_empty());
+
+    // Store the annotation as it is typed, so it survives leaving the screen
+    use_effect(move || {
+        let mut hidden: Vec<(EntryRole, usize)> = hidden().into_iter().collect();
+        hidden.sort();
+        let mut salts: Vec<(EntryRole, usize, Hash)> = salts()
+            .into_iter()
+            .map(|((role, i), salt)| (role, i, salt))
+            .collect();
+        salts.sort_by_key(|(role, i, _)| (*role, *i));
+        let annotation = Annotation {
+            title: title(),
+            description: description(),
+            funder_notes: inputs(),
+            payee_notes: outputs(),
+            hidden,
+            salts: if salts_used() { salts } else { vec![] },
+        };
+
+        if let Some(e) = global.peek().annotations_error.clone() {
+            warning.set(format!("Annotations failed to load ({e}), changes are not saved until they do"));
+            return;
+        }
+        let stored = global.peek().annotations.get(&transaction).cloned();
+        if (stored.is_none() && annotation.is_empty()) || stored.as_ref() == Some(&annotation) {
+            return;
+        }
+        global.with_mut(|g| {
+            if annotation.is_empty() {
+                g.annotations.remove(&transaction);
+            } else {
+                g.annotations.insert(transaction, annotation);
+            }
+            if let Err(e) = save_annotations(&g.annotations, &g.pin) {
+                warning.set(format!("Failed to save annotation: {e}"));
+            }
+        });
+    });
+
+    let entry_details = move |role: EntryRole, i: usize| -> Option<EntryDetails> {
+        let tx = loaded_tx()?;
+        Some(match role {
+            EntryRole::Funder => {
+                let input = tx.transaction.inputs.get(i)?;
+                EntryDetails {
+                    address: input.output_owner.dump_base36(),
+                    amount: input_amounts().get(i).copied().flatten(),
+                    note: inputs().get(i)?.clone(),
+                    signature: Some(input.signature.map_or("None".to_string(), |s| s.dump_base36())),
+                }
+            }
+            EntryRole::Payee => {
+                let output = tx.transaction.outputs.get(i)?;
+                EntryDetails {
+                    address: output.receiver.dump_base36(),
+                    amount: Some(output.amount),
+                    note: outputs().get(i)?.clone(),
+                    signature: None,
+                }
+            }
+        })
+    };
+
+    let entry = move |role: EntryRole, i: usize| -> Result<ReceiptEntry, anyhow::Error> {
+        let details = entry_details(role, i).ok_or_else(|| anyhow!("The transaction is not loaded"))?;
+        if hidden.read().contains(&(role, i)) {
+            Ok(ReceiptEntry::Hidden {
+                commitment: details.commitment(&salts.read()[&(role, i)])?,
+            })
+        } else {
+            Ok(ReceiptEntry::Shown(details))
+        }
+    };
+
+    let build_receipt = move || -> Result<SignedReceipt, anyhow::Error> {
+        let tx = loaded_tx().ok_or_else(|| anyhow!("The transaction is not loaded"))?;
+
+        let funders = (0..tx.transaction.inputs.len())
+            .map(|i| entry(EntryRole::Funder, i))
+            .collect::<Result<_, _>>()?;
+        let payees = (0..tx.transaction.outputs.len())
+            .map(|i| entry(EntryRole::Payee, i))
+            .collect::<Result<_, _>>()?;
+
+        let name = signer().ok_or_else(|| anyhow!("None of your wallets took part in this transaction"))?;
+        let private = *global()
+            .wallets
+            .get(&name)
+            .ok_or_else(|| anyhow!("Wallet {name} is no longer in the vault"))?;
+        let creator = private.to_public();
+
+        ReceiptPayload {
+            version: RECEIPT_VERSION,
+            title: title(),
+            description: description(),
+            transaction_id: transaction.dump_base36(),
+            block_hash: tx.in_block.dump_base36(),
+            height: tx.at_height,
+            timestamp: tx.transaction.timestamp,
+            funders,
+            payees,
+            creator: creator.dump_base36(),
+            creator_roles: EntryRole::of(&creator, &tx.transaction),
+        }
+        .sign(private)
+    };
+
+    let mut save_file = move |title: &str, file_name: String, filter: &str, extension: &str, contents: &[u8]| {
+        if let Some(path) = FileDialog::new()
+            .set_title(title)
+            .set_file_name(file_name)
+            .add_filter(filter, &[extension])
+            .save_file()
+        {
+            if let Err(e) = fs::write(&path, contents) {
+                status.set(format!("Failed to save file: {e}"));
+            }
+        }
+    };
+
+    let mut save_receipt = move |contents: &[u8], extension: &str, filter: &str| {
+        save_file(
+            "Save Annotated Transaction",
+            format!("tx-{}.{extension}", &transaction.dump_base36()[0..8]),
+            filter,
+            extension,
+            contents,
+        );
+    };
+
+    // A reveal opens one hidden entry, it only matches receipts exported with the same salt
+    let mut save_reveal = move |role: EntryRole, i: usize| {
+        let Some(details) = entry_details(role, i) else {
+            return;
+        };
+        let reveal = EntryReveal {
+            transaction_id: transaction.dump_base36(),
+            role,
+            index: i,
+            salt: salts.read()[&(role, i)],
+            details,
+        };
+        match reveal.to_json() {
+            Ok(json) => save_file(
+                "Save Reveal",
+                format!(
+                    "tx-{}-{}-{}.reveal.json",
+                    &transaction.dump_base36()[0..8],
+                    role.label(),
+                    i + 1
+                ),
+                "JSON",
+                "json",
+                json.as_bytes(),
+            ),
+            Err(e) => status.set(format!("Failed to create reveal: {e}")),
+        }
+    };
+
+    let mut export_receipt = move |extension: &str,
+                                   filter: &str,
+                                   render: fn(&SignedReceipt) -> Result<Vec<u8>, anyhow::Error>| {
+        match build_receipt().and_then(|receipt| render(&receipt)) {
+            Ok(contents) => {
+                if !hidden.peek().is_empty() {
+                    salts_used.set(true);
+                }
+                save_receipt(&contents, extension, filter)
+            }
+            Err(e) => status.set(format!("Failed to create receipt: {e}")),
+        }
+    };
+
+    let mut toggle_hidden = move |role: EntryRole, i: usize| {
+        let mut hidden = hidden.write();
+        if !hidden.remove(&(role, i)) {
+            hidden.insert((role, i));
+        }
+    };
 
     if !status().is_empty() {
         rsx! {
@@ -85,6 +355,9 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                     "Annotate Transaction"
                 }
                 CopyBox { title: "Transaction ID", text: transaction.dump_base36() }
+                if !warning().is_empty() {
+                    p { class: "text-yellow-400", "{warning}" }
+                }
 
                 input {
                     value: "{title}",
@@ -112,7 +385,6 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                                     class: "flex gap-5 items-center",
                                     CopyBox { text: input.output_owner.dump_base36() }
                                     input {
-                                        class: format!("{}", if inputs()[i] == "=hidden=" { "hidden" } else { "" }),
                                         value: inputs()[i].clone(),
                                         onchange: move |e| {
                                             inputs.write()[i] = e.value();
@@ -120,14 +392,16 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                                         placeholder: "Funder note..."
                                     }
                                     button {
-                                        onclick: move |_| {
-                                            if inputs()[i] == "=hidden=" {
-                                                inputs.write()[i] = "".to_string();
-                                            } else {
-                                                inputs.write()[i] = "=hidden=".to_string();
-                                            }
-                                        },
-                                        if inputs()[i] == "=hidden=" { "Show" } else { "Hide" }
+                                        onclick: move |_| toggle_hidden(EntryRole::Funder, i),
+                                        if hidden.read().contains(&(EntryRole::Funder, i)) { "Show" } else { "Hide" }
+                                    }
+                                    if hidden.read().contains(&(EntryRole::Funder, i)) {
+                                        button {
+                                            title: "Save a file that discloses this entry to whoever holds the receipt",
+                                            class: "bg-transparent! border! border-[var(--border)]! text-nowrap",
+                                            onclick: move |_| save_reveal(EntryRole::Funder, i),
+                                            "Save reveal"
+                                        }
                                     }
                                 }
                             }
@@ -145,7 +419,6 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                                     class: "flex gap-5 items-center w-full",
                                     CopyBox { text: output.receiver.dump_base36() }
                                     input {
-                                        class: format!("{}", if outputs()[i] == "=hidden=" { "hidden" } else { "" }),
                                         value: outputs()[i].clone(),
                                         onchange: move |e| {
                                             outputs.write()[i] = e.value();
@@ -153,14 +426,16 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                                         placeholder: "Payee note..."
                                     }
                                     button {
-                                        onclick: move |_| {
-                                            if outputs()[i] == "=hidden=" {
-                                                outputs.write()[i] = "".to_string();
-                                            } else {
-                                                outputs.write()[i] = "=hidden=".to_string();
-                                            }
-                                        },
-                                        if outputs()[i] == "=hidden=" { "Show" } else { "Hide" }
+                                        onclick: move |_| toggle_hidden(EntryRole::Payee, i),
+                                        if hidden.read().contains(&(EntryRole::Payee, i)) { "Show" } else { "Hide" }
+                                    }
+                                    if hidden.read().contains(&(EntryRole::Payee, i)) {
+                                        button {
+                                            title: "Save a file that discloses this entry to whoever holds the receipt",
+                                            class: "bg-transparent! border! border-[var(--border)]! text-nowrap",
+                                            onclick: move |_| save_reveal(EntryRole::Payee, i),
+                                            "Save reveal"
+                                        }
                                     }
                                 }
                             }
@@ -169,54 +444,46 @@ pub fn AnnotateTransaction(transaction: TransactionId) -> Element {
                 }
 
                 h4 { "Annotate sign, and export" }
-                button {
-                    onclick: move |_| {
-                        let header = format!("Transaction Confirmation\n\n{title}\n{description}\n\nNetwork Information\nTransaction ID: {}\nTime: {}\nIncluded in block: #{}: {}\n\nFunders\n", transaction.dump_base36(), chrono::Local.timestamp_opt(tx.transaction.timestamp as i64, 0).unwrap().format("%A, %b %d %Y %I:%M %p %Z"), tx.at_height, tx.in_block.dump_base36());
-
-                        let mut funders = String::new();
-
-                        for (i, input) in tx.transaction.inputs.iter().enumerate() {
-                            if inputs()[i] == "=hidden=" {
-                                continue;
-                            }
-                            let funder = format!("Funder #{}: {}\nSender address: {}\nSignature: {}\nTotal: -{} SNAP\n\n", i + 1, inputs()[i], input.output_owner.dump_base36(), input.signature.unwrap().dump_base36(), to_snap(input_amounts().unwrap()[i]));
-                            funders += &funder;
-                        }
-
-                        let payee = format!("Payees\n");
-
-                        let mut payees = String::new();
-
-                        for (i, output) in tx.transaction.outputs.iter().enumerate() {
-                            if outputs()[i] == "=hidden=" {
-                                continue;
-                            }
-                            let payee = format!("Payee #{}: {}\nPayee address: {}\nTotal: +{} SNAP\n\n", i + 1, outputs()[i], output.receiver.dump_base36(), to_snap(output.amount));
-                            payees += &payee;
-                        }
-
-                        let all = header + &funders + &payee + &payees;
-                        let selected_wallet = global().selected_wallet;
-                        let mut private = global.write().wallets[&selected_wallet];
-
-                        let annotate_sign = Signature::new_signature(&mut private, all.as_bytes());
-
-                        let footer = format!("Annotation Information\nCreated by: {}\nCreator signature: {}", private.to_public().dump_base36(), annotate_sign.dump_base36());
-
-                        let export = all + &footer;
-
-                        if let Some(path) = FileDialog::new()
-                            .set_title("Save Annotated Transaction")
-                            .set_file_name(&format!("tx-{}.txt", &transaction.dump_base36()[0..8]))
-                            .add_filter("Text", &["txt"])
-                            .save_file()
-                        {
-                            if let Err(e) = fs::write(&path, export) {
-                                status.set(format!("Failed to save file: {e}"));
-                            }
+                if parties.is_empty() {
+                    p {
+                        class: "text-yellow-400",
+                        "None of your wallets funded or received this transaction, so none of them can sign a receipt for it"
+                    }
+                } else {
+                    label { "Sign as" }
+                    select {
+                        value: signer().unwrap_or_default(),
+                        onchange: move |e| signer.set(Some(e.value())),
+                        for (name, roles) in parties {
+                            option { value: "{name}", "{name} ({EntryRole::describe(&roles)})" }
                         }
-                    },
-                    "Download"
+                    }
+                }
+                div {
+                    class: "flex gap-5",
+                    button {
+                        disabled: signer().is_none(),
+                        onclick: move |_| export_receipt("pdf", "PDF", render_pdf),
+                        "Download PDF"
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: signer().is_none(),
+                        onclick: move |_| export_receipt("html", "HTML", |receipt| Ok(render_html(receipt)?.into_bytes())),
+                        "Download HTML"
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: signer().is_none(),
+                        onclick: move |_| export_receipt("txt", "Text", |receipt| Ok(receipt.render_text()?.into_bytes())),
+                        "Download text"
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: signer().is_none(),
+                        onclick: move |_| export_receipt("json", "JSON", |receipt| Ok(receipt.to_json()?.into_bytes())),
+                        "Download JSON"
+                    }
                 }
             }
         }
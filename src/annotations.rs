@@ -0,0 +1,65 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use snap_coin::{core::transaction::TransactionId, crypto::Hash};
+
+use crate::{
+    encryption::{decrypt_bytes, encrypt_bytes},
+    receipt::EntryRole,
+    write_atomic,
+};
+
+/// What the user wrote about a transaction in `AnnotateTransaction`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Annotation {
+    pub title: String,
+    pub description: String,
+    /// One note per transaction input
+    pub funder_notes: Vec<String>,
+    /// One note per transaction output
+    pub payee_notes: Vec<String>,
+    /// Entries left out of exported receipts
+    pub hidden: Vec<(EntryRole, usize)>,
+    /// Salts of the receipt commitments, kept so reveals still open receipts that were already handed out
+    pub salts: Vec<(EntryRole, usize, Hash)>,
+}
+
+impl Annotation {
+    /// Whether nothing is worth keeping, salts always are since receipts may be out there
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty()
+            && self.description.is_empty()
+            && self.funder_notes.iter().all(String::is_empty)
+            && self.payee_notes.iter().all(String::is_empty)
+            && self.hidden.is_empty()
+            && self.salts.is_empty()
+    }
+}
+
+pub type Annotations = HashMap<TransactionId, Annotation>;
+
+/// Returns annotations file path
+pub fn annotations_path() -> Result<PathBuf, Error> {
+    let home = dirs::home_dir().ok_or_else(|| Error::msg("Could not determine home directory"))?;
+    Ok(home.join(".snap-coin-wallet-annotations"))
+}
+
+/// Load the annotations, an empty set when none were saved yet
+pub fn load_annotations(pin: &str) -> Result<Annotations, Error> {
+    let path = annotations_path()?;
+    if !path.exists() {
+        return Ok(Annotations::new());
+    }
+    let decrypted = decrypt_bytes(&fs::read(path)?, pin)
+        .ok_or_else(|| Error::msg("Failed to decrypt annotations"))?;
+    Ok(serde_json::from_slice(&decrypted)?)
+}
+
+pub fn save_annotations(annotations: &Annotations, pin: &str) -> Result<(), Error> {
+    let encrypted = encrypt_bytes(&serde_json::to_vec(annotations)?, pin)
+        .ok_or_else(|| Error::msg("Failed to encrypt annotations"))?;
+    write_atomic(&annotations_path()?, &encrypted)?;
+    Ok(())
+}
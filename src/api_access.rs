@@ -0,0 +1,230 @@
+use std::net::IpAddr;
+
+use chrono::{Local, TimeZone};
+use dioxus::prelude::*;
+
+use crate::{
+    connection_settings::{
+        load_connection_settings, save_connection_settings, ApiBind, ApiPermission, ApiToken,
+        ConnectionSettings,
+    },
+    copy_box::CopyBox,
+    node_api::{generate_api_token, hash_api_token},
+    GlobalContext,
+};
+
+impl ApiPermission {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiPermission::ReadOnly => "Read only",
+            ApiPermission::Submit => "Read and submit",
+        }
+    }
+}
+
+fn format_created(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map_or("-".to_string(), |created| {
+            created.format("%b %d, %Y").to_string()
+        })
+}
+
+/// Tokens and addresses other machines use the API of the in-wallet node with
+#[component]
+pub fn ApiAccessSettings() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut status = use_signal(|| "".to_string());
+    let mut settings = use_signal(|| match load_connection_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            status.set(format!("Failed to load connection settings: {e}"));
+            ConnectionSettings::default()
+        }
+    });
+    let mut token_name = use_signal(|| "".to_string());
+    let mut token_permission = use_signal(|| ApiPermission::ReadOnly);
+    // Only ever held here, the settings keep its hash
+    let mut new_token: Signal<Option<(String, String)>> = use_signal(|| None);
+    let mut new_ip = use_signal(|| "".to_string());
+
+    // Saved for the next start, and handed to the running node
+    let mut update_access = move |update: &dyn Fn(&mut ConnectionSettings)| {
+        settings.with_mut(|settings| {
+            update(settings);
+            if let Err(e) = save_connection_settings(settings) {
+                status.set(format!("Failed to save API access: {e}"));
+            }
+            if let Some(node) = &global.peek().internal_node {
+                node.supervisor.set_api_access(settings.api_access.clone());
+            }
+        });
+    };
+
+    let mut generate = move || {
+        let name = token_name().trim().to_string();
+        if name.is_empty() {
+            status.set("Name the token after who it is for".to_string());
+            return;
+        }
+        let token = generate_api_token();
+        let entry = ApiToken {
+            name: name.clone(),
+            hash: hash_api_token(&token),
+            permission: token_permission(),
+            created: Local::now().timestamp(),
+        };
+        update_access(&|settings| settings.api_access.tokens.push(entry.clone()));
+        new_token.set(Some((name, token)));
+        token_name.set("".to_string());
+        status.set("".to_string());
+    };
+
+    let listens_on_lan = match &global().internal_node {
+        Some(node) => node.supervisor.config().options.api_bind == ApiBind::Lan,
+        None => settings().node.api_bind == ApiBind::Lan,
+    };
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "API Access" }
+            p {
+                class: "text-neutral-400",
+                if listens_on_lan {
+                    "Who may use the API of the in-wallet node from other machines. This machine always may."
+                } else {
+                    "The API of the in-wallet node only listens on this machine, this applies once it is opened to the network on the connection screen."
+                }
+            }
+            if listens_on_lan {
+                p { class: "text-yellow-400 text-sm -mt-4", "Tokens and requests travel over the network unencrypted, anyone on the network can read them. Only open the API on a network you trust." }
+            }
+
+            label {
+                class: "flex items-center gap-2",
+                input {
+                    r#type: "checkbox",
+                    checked: settings().api_access.require_token,
+                    onchange: move |e| update_access(&|settings| settings.api_access.require_token = e.checked()),
+                }
+                "Require an API token from other machines"
+            }
+            p { class: "text-sm text-neutral-400 -mt-4", "Other machines need a token to submit transactions or blocks, without one they may only read." }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Tokens" }
+
+                    div {
+                        class: "flex gap-2",
+                        input {
+                            class: "flex-1 min-w-0",
+                            r#type: "text",
+                            placeholder: "Who is this token for?",
+                            value: "{token_name}",
+                            oninput: move |e| token_name.set(e.value()),
+                        }
+                        select {
+                            class: "bg-neutral-800 p-2 rounded",
+                            onchange: move |e| token_permission.set(if e.value() == "submit" { ApiPermission::Submit } else { ApiPermission::ReadOnly }),
+                            option { value: "read_only", selected: token_permission() == ApiPermission::ReadOnly, "{ApiPermission::ReadOnly.label()}" }
+                            option { value: "submit", selected: token_permission() == ApiPermission::Submit, "{ApiPermission::Submit.label()}" }
+                        }
+                        button {
+                            onclick: move |_| generate(),
+                            "Generate"
+                        }
+                    }
+
+                    if let Some((name, token)) = new_token() {
+                        div {
+                            class: "bg-neutral-800 p-4 rounded flex flex-col gap-2",
+                            p { class: "text-sm", "Token for {name}. It is only shown now, copy it to the other machine." }
+                            CopyBox { text: token }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs self-start",
+                                onclick: move |_| new_token.set(None),
+                                "Done"
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "flex flex-col gap-2 overflow-auto pr-2",
+                        if settings().api_access.tokens.is_empty() {
+                            p { class: "text-neutral-400", "No tokens" }
+                        }
+                        for token in settings().api_access.tokens {
+                            div {
+                                class: "bg-neutral-800 p-4 rounded grid grid-cols-[1fr_10rem_8rem_max-content] items-center gap-x-4 text-sm",
+                                span { class: "truncate", "{token.name}" }
+                                span { "{token.permission.label()}" }
+                                span { class: "text-neutral-400", "{format_created(token.created)}" }
+                                button {
+                                    class: "bg-transparent! border! border-red-400! text-red-400! text-xs",
+                                    onclick: move |_| {
+                                        let hash = token.hash.clone();
+                                        update_access(&|settings| settings.api_access.tokens.retain(|token| token.hash != hash));
+                                    },
+                                    "Revoke"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-auto",
+                    h3 { class: "font-semibold text-lg", "Allowed addresses" }
+                    p { class: "text-sm text-neutral-400", "Only these machines may connect, any may when the list is empty." }
+                    div {
+                        class: "flex gap-2",
+                        input {
+                            class: "flex-1 min-w-0",
+                            r#type: "text",
+                            placeholder: "192.168.1.20",
+                            value: "{new_ip}",
+                            oninput: move |e| new_ip.set(e.value()),
+                        }
+                        button {
+                            class: "bg-transparent! border! border-[var(--border)]!",
+                            onclick: move |_| match new_ip().trim().parse::<IpAddr>() {
+                                Ok(ip) => {
+                                    update_access(&|settings| {
+                                        settings.api_access.allowed_ips.insert(ip);
+                                    });
+                                    new_ip.set("".to_string());
+                                }
+                                Err(_) => status.set(format!("{} is not an IP address", new_ip().trim())),
+                            },
+                            "Allow"
+                        }
+                    }
+                    if settings().api_access.allowed_ips.is_empty() {
+                        p { class: "text-sm text-neutral-500", "Any address" }
+                    }
+                    for ip in settings().api_access.allowed_ips {
+                        div {
+                            class: "bg-neutral-800 p-2 rounded flex items-center justify-between gap-2 text-sm",
+                            span { class: "font-mono truncate", "{ip}" }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                onclick: move |_| update_access(&|settings| {
+                                    settings.api_access.allowed_ips.remove(&ip);
+                                }),
+                                "Remove"
+                            }
+                        }
+                    }
+                    p { "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use snap_coin::{
+    api::{client::Client, requests::RequestResponseError},
+    blockchain_data_provider::BlockchainDataProviderError,
+    core::transaction::{Transaction, TransactionId, TransactionInput},
+};
+use thiserror::Error;
+
+/// Why a chain lookup failed
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ChainError {
+    #[error("Transaction {0} was not found, the node may be pruned or behind")]
+    MissingTransaction(String),
+
+    #[error("The node is still syncing, some data is not available yet")]
+    Syncing,
+
+    #[error("Lost connection to the node")]
+    ConnectionLost,
+
+    #[error("Node error: {0}")]
+    Node(String),
+}
+
+impl ChainError {
+    pub fn missing(transaction_id: &TransactionId) -> Self {
+        ChainError::MissingTransaction(transaction_id.dump_base36())
+    }
+
+    /// Data missing while the node is syncing is most likely just not downloaded yet
+    pub fn or_syncing(self, syncing: bool) -> Self {
+        match self {
+            ChainError::MissingTransaction(_) if syncing => ChainError::Syncing,
+            e => e,
+        }
+    }
+}
+
+impl From<BlockchainDataProviderError> for ChainError {
+    fn from(e: BlockchainDataProviderError) -> Self {
+        match e {
+            BlockchainDataProviderError::RequestResponseError(RequestResponseError::Stream) => {
+                ChainError::ConnectionLost
+            }
+            e => ChainError::Node(e.to_string()),
+        }
+    }
+}
+
+/// Fetch a transaction that is expected to be on chain
+pub async fn require_transaction(
+    client: &Client,
+    transaction_id: &TransactionId,
+) -> Result<Transaction, ChainError> {
+    client
+        .get_transaction(transaction_id)
+        .await?
+        .ok_or_else(|| ChainError::missing(transaction_id))
+}
+
+/// Amount of the output spent by an input
+pub async fn input_amount(client: &Client, input: &TransactionInput) -> Result<u64, ChainError> {
+    require_transaction(client, &input.transaction_id)
+        .await?
+        .outputs
+        .get(input.output_index)
+        .map(|output| output.amount)
+        .ok_or_else(|| ChainError::missing(&input.transaction_id))
+}
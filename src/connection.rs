@@ -1,37 +1,259 @@
-use std::{env::home_dir, fs, net::ToSocketAddrs, path::PathBuf, sync::Arc, time::Duration};
+use std::{net::ToSocketAddrs, path::PathBuf, sync::Arc, time::Duration};
 
-use anyhow::anyhow;
 use dioxus::prelude::*;
-use snap_coin::{
-    api::{api_server::Server, client::Client},
-    full_node::{
-        auto_peer::start_auto_peer, auto_reconnect::start_auto_reconnect, connect_peer,
-        create_full_node, ibd::ibd_blockchain,
+use rfd::FileDialog;
+use tokio::time::sleep;
+
+use crate::{
+    connection_settings::{
+        load_connection_settings, parse_address_list, save_connection_settings, ApiBind,
+        ConnectionMode, ConnectionSettings,
+    },
+    node_log::NodeLog,
+    node_storage::{disk_usage, format_bytes, has_node_data, move_node_data, DiskUsage, LOW_SPACE_BYTES},
+    node_api::{connect_api, FIRST_UNPRIVILEGED_PORT},
+    node_supervisor::{
+        launch_node, restart_node, stop_node, NodeConfig, NodeLifecycle, PeerOutcome, PeerReport,
+        SyncProgress,
     },
+    settings::save_settings,
+    GlobalContext, Route,
 };
-use tokio::{net::lookup_host, time::sleep};
 
-use crate::{node_log::NodeLog, GlobalContext, NodeHandle, Route};
+/// Time to skip the automatic connection after unlocking
+const AUTO_CONNECT_DELAY: Duration = Duration::from_secs(3);
+/// How often the disk usage of the node data directory is measured
+const DISK_USAGE_INTERVAL: Duration = Duration::from_secs(10);
 
 #[component]
 pub fn Connection() -> Element {
     let mut status = use_signal(|| "".to_string());
-    let mut in_app_peers_setting =
-        use_signal(|| "node.snap-coin.net:8998,node.snap-coin.net:7887".to_string());
-    let mut external_api_setting = use_signal(|| "127.0.0.1:3003".to_string());
-
-    let global = use_context::<Signal<GlobalContext>>();
+    let mut connection_settings = use_signal(|| match load_connection_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            status.set(format!("Failed to load connection settings: {e}"));
+            ConnectionSettings::default()
+        }
+    });
+    let mut in_app_peers_setting = use_signal(|| connection_settings.peek().peers.join(","));
+    let mut external_api_setting = use_signal(|| connection_settings.peek().external_apis.join(","));
+    let mut hashing_threads_setting = use_signal(|| {
+        connection_settings.peek().node.hashing_threads.map_or("".to_string(), |threads| threads.to_string())
+    });
+    let mut api_port_setting =
+        use_signal(|| connection_settings.peek().node.api_port.map_or("".to_string(), |port| port.to_string()));
+
+    let mut global = use_context::<Signal<GlobalContext>>();
+    let mut external_token_setting = use_signal(|| global.peek().settings.external_api_token.clone());
     let navigator = use_navigator();
     let mut started_node = use_signal(|| false);
-    let node_port = use_signal(|| "".to_string());
+
+    // Remember what was used, once it worked
+    let mut remember = move |mode: ConnectionMode| {
+        connection_settings.with_mut(|settings| {
+            settings.last_mode = Some(mode);
+            if let Err(e) = save_connection_settings(settings) {
+                status.set(format!("Failed to save connection settings: {e}"));
+            }
+        });
+    };
+
+    let node_running = use_memo(move || started_node() || global().internal_node.is_some());
+    // Node data being moved into the data directory, the node must not start on it halfway
+    let mut is_moving = use_signal(|| false);
+
+    let mut start_node = move || {
+        if node_running() || is_moving() {
+            return;
+        }
+
+        started_node.set(true);
+
+        connection_settings.with_mut(|settings| {
+            settings.peers = parse_address_list(&in_app_peers_setting());
+            settings.node.hashing_threads = hashing_threads_setting().trim().parse().ok();
+            settings.node.api_port = api_port_setting()
+                .trim()
+                .parse()
+                .ok()
+                .filter(|port| *port >= FIRST_UNPRIVILEGED_PORT);
+        });
+        let config = NodeConfig {
+            peers: connection_settings().peers,
+            options: connection_settings().node,
+            allowed_peers: connection_settings().allowed_peers,
+            denied_peers: connection_settings().denied_peers,
+            api_access: connection_settings().api_access,
+        };
+
+        spawn(async move {
+            match launch_node(global, config).await {
+                Ok(supervisor) => {
+                    // The port the OS picked is tried again next time
+                    if let Some(address) = supervisor.api_address() {
+                        connection_settings.write().node.last_api_port = Some(address.port());
+                    }
+                    remember(ConnectionMode::InApp);
+                    if supervisor.wait_for(|state| *state == NodeLifecycle::Synced).await == NodeLifecycle::Synced {
+                        navigator.push(Route::Home);
+                    }
+                }
+                Err(e) => status.set(e),
+            }
+            started_node.set(false);
+        });
+    };
+
+    let mut connect_external = move || {
+        let apis = parse_address_list(&external_api_setting());
+        let token = external_token_setting().trim().to_string();
+        connection_settings.with_mut(|settings| {
+            settings.external_apis = apis.clone();
+        });
+
+        spawn(async move {
+            // The first API that answers wins
+            let mut last_error = "No node API address given".to_string();
+            for api in apis {
+                let addr = match api.to_socket_addrs().map(|mut addrs| addrs.next()) {
+                    Ok(Some(addr)) => addr,
+                    _ => {
+                        last_error = format!("Could not resolve {}", api);
+                        continue;
+                    }
+                };
+
+                let token = (!token.is_empty()).then_some(token.clone());
+                match connect_api(addr, token.as_deref()).await {
+                    Ok(client) => {
+                        {
+                            let mut g = global.write();
+                            g.api_client = Some(Arc::new(client));
+                            // Kept with the settings, which are encrypted with the PIN
+                            let saved = token.clone().unwrap_or_default();
+                            if g.settings_error.is_none() && g.settings.external_api_token != saved {
+                                g.settings.external_api_token = saved;
+                                if let Err(e) = save_settings(&g.settings, &g.pin) {
+                                    status.set(format!("Failed to save the API token: {e}"));
+                                }
+                            }
+                            g.api_token = token;
+                        }
+                        remember(ConnectionMode::External);
+
+                        navigator.push(Route::Home);
+                        return;
+                    }
+                    Err(e) => last_error = format!("{}: {}", api, e),
+                }
+            }
+            status.set(last_error);
+        });
+    };
+
+    // Measured off the UI thread, walking a synced chain takes a while
+    let mut usage: Signal<Option<DiskUsage>> = use_signal(|| None);
+    let refresh_usage = move || {
+        let Ok(data_dir) = connection_settings.peek().node.data_dir() else {
+            return;
+        };
+        spawn(async move {
+            if let Ok(measured) = tokio::task::spawn_blocking(move || disk_usage(&data_dir)).await {
+                usage.set(Some(measured));
+            }
+        });
+    };
+    use_future(move || async move {
+        loop {
+            refresh_usage();
+            sleep(DISK_USAGE_INTERVAL).await;
+        }
+    });
+
+    // Saved along with the directory, leaving the screen does not forget the old chain
+    let mut save_data_dir = move |dir: Option<PathBuf>, pending_move: Option<PathBuf>| {
+        connection_settings.with_mut(|settings| {
+            settings.node.data_dir = dir;
+            settings.node.pending_move = pending_move;
+            if let Err(e) = save_connection_settings(settings) {
+                status.set(format!("Failed to save connection settings: {e}"));
+            }
+        });
+        refresh_usage();
+    };
+    let mut set_pending_move = move |pending_move: Option<PathBuf>| {
+        let dir = connection_settings.peek().node.data_dir.clone();
+        save_data_dir(dir, pending_move);
+    };
+
+    // Reconnect the way it worked last time, unless the user skips it
+    let mut auto_connecting = use_signal(|| {
+        let settings = connection_settings.peek();
+        // Starting while the chain waits to be moved would download it again from scratch
+        let move_pending = settings.last_mode == Some(ConnectionMode::InApp) && settings.node.pending_move.is_some();
+        settings.auto_connect
+            && settings.last_mode.is_some()
+            && !move_pending
+            && global.peek().api_client.is_none()
+            && global.peek().internal_node.is_none()
+    });
+    use_future(move || async move {
+        if !auto_connecting() {
+            return;
+        }
+        sleep(AUTO_CONNECT_DELAY).await;
+        if !auto_connecting() {
+            return;
+        }
+        auto_connecting.set(false);
+        match connection_settings.peek().last_mode {
+            Some(ConnectionMode::InApp) => start_node(),
+            Some(ConnectionMode::External) => connect_external(),
+            None => {}
+        }
+    });
 
     rsx! {
         div {
             class: "p-5",
             h1 { "Set up a Snap Coin Node" }
 
+            if auto_connecting() {
+                div {
+                    class: "flex items-center gap-4 p-5",
+                    p {
+                        match connection_settings().last_mode {
+                            Some(ConnectionMode::InApp) => "Starting the in-wallet node like last time...",
+                            _ => "Connecting to your node like last time...",
+                        }
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        onclick: move |_| auto_connecting.set(false),
+                        "Skip"
+                    }
+                }
+            }
+
+            label {
+                class: "flex items-center gap-2 p-5",
+                input {
+                    r#type: "checkbox",
+                    checked: connection_settings().auto_connect,
+                    onchange: move |e| {
+                        connection_settings.with_mut(|settings| {
+                            settings.auto_connect = e.checked();
+                            if let Err(e) = save_connection_settings(settings) {
+                                status.set(format!("Failed to save connection settings: {e}"));
+                            }
+                        });
+                    },
+                }
+                "Connect automatically after unlocking"
+            }
+
             div {
-                class: format!("grid w-full divide-x divide-gray-600 {}", if started_node() { "grid-cols-1" } else { "grid-cols-2" }),
+                class: format!("grid w-full divide-x divide-gray-600 {}", if node_running() { "grid-cols-1" } else { "grid-cols-2" }),
 
                 div {
                     class: "p-5 flex flex-col gap-5",
@@ -45,151 +267,170 @@ pub fn Connection() -> Element {
                         oninput: move |e| in_app_peers_setting.set(e.value())
                     }
 
-                    button {
-                        disabled: started_node,
-                        onclick: move |_| {
-                            if started_node() {
-                                return;
-                            }
+                    label { "Data directory" }
+                    div {
+                        class: "flex items-center gap-2",
+                        input {
+                            class: "flex-1 min-w-0",
+                            type: "text",
+                            readonly: true,
+                            value: connection_settings().node.data_dir().map(|dir| dir.display().to_string()).unwrap_or_default(),
+                        }
+                        button {
+                            class: "bg-transparent! border! border-[var(--border)]!",
+                            disabled: node_running() || is_moving(),
+                            onclick: move |_| {
+                                let Some(dir) = FileDialog::new()
+                                    .set_title("Node Data Directory")
+                                    .pick_folder()
+                                else {
+                                    return;
+                                };
+                                let Ok(current) = connection_settings().node.data_dir() else {
+                                    return;
+                                };
+                                if dir == current {
+                                    return;
+                                }
+                                // A move still pending keeps pointing at where the chain actually is
+                                let pending_move = connection_settings().node.pending_move.or(has_node_data(&current).then_some(current));
+                                save_data_dir(Some(dir), pending_move);
+                            },
+                            "Choose"
+                        }
+                    }
 
-                            started_node.set(true);
-
-                            let peers = in_app_peers_setting();
-                            let mut status = status.clone();
-                            let mut global = global.clone();
-                            let navigator = navigator.clone();
-                            let mut node_port = node_port.clone();
-
-                            // Channel to communicate back to UI safely
-                            let (tx, rx) = futures_channel::oneshot::channel();
-                            let (synced_tx, synced_rx) = futures_channel::oneshot::channel();
-                            let mut tx = Some(tx);
-
-                            // ---- Spawn dedicated node thread ----
-                            std::thread::spawn(move || {
-                                let rt = tokio::runtime::Builder::new_multi_thread()
-                                    .enable_all()
-                                    .build()
-                                    .unwrap();
-
-                                rt.block_on(async move {
-                                    let result: anyhow::Result<()> = async {
-                                        let node_path = home_dir().unwrap().join("node-mainnet");
-                                        let node_path = node_path.to_str().unwrap().to_string();
-
-                                        if fs::exists(node_path.clone() + "/logs/")? {
-                                            fs::remove_dir_all(node_path.clone() + "/logs/")?;
-                                            fs::create_dir_all(node_path.clone() + "/logs/")?;
-                                        }
-
-                                        let (blockchain, node_state, log_file) = create_full_node(&node_path, false, None);
-
-                                        let mut resolved_peers = vec![];
-                                        for peer in peers.replace(" ", "").split(",") {
-                                            let peer = lookup_host(peer)
-                                                .await?
-                                                .next()
-                                                .ok_or(anyhow!("Could not resolve {}", peer))?;
-                                            resolved_peers.push(peer);
-
-                                            connect_peer(peer, &blockchain, &node_state).await?;
-                                        }
-
-                                        let auto_peer = start_auto_peer(node_state.clone(), blockchain.clone(), vec![]);
-
-                                        let handle = NodeHandle {
-                                            _node_state: node_state.clone(),
-                                            _blockchain: blockchain.clone(),
-                                            log_file,
+                    if let Some(previous) = connection_settings().node.pending_move {
+                        div {
+                            class: "flex flex-col gap-2 text-sm",
+                            p { "{previous.display()} still holds the chain. Move it, or download it again into the new directory." }
+                            div {
+                                class: "flex gap-2",
+                                button {
+                                    disabled: node_running() || is_moving(),
+                                    onclick: move |_| {
+                                        let Ok(target) = connection_settings().node.data_dir() else {
+                                            return;
                                         };
+                                        let previous = previous.clone();
+                                        is_moving.set(true);
+                                        spawn(async move {
+                                            let moved = tokio::task::spawn_blocking(move || move_node_data(&previous, &target)).await;
+                                            match moved {
+                                                Ok(Ok(())) => set_pending_move(None),
+                                                Ok(Err(e)) => status.set(format!("Failed to move node data: {e}")),
+                                                Err(e) => status.set(format!("Failed to move node data: {e}")),
+                                            }
+                                            is_moving.set(false);
+                                            refresh_usage();
+                                        });
+                                    },
+                                    if is_moving() { "Moving..." } else { "Move existing data" }
+                                }
+                                button {
+                                    class: "bg-transparent! border! border-[var(--border)]!",
+                                    disabled: is_moving(),
+                                    onclick: move |_| set_pending_move(None),
+                                    "Start fresh"
+                                }
+                            }
+                        }
+                    }
 
-                                        let port = rand::random::<u16>();
-                                        let api_server = Server::new(port as u32, blockchain.clone(), node_state.clone());
-
-                                        // ---- START API SERVER ----
-                                        api_server.listen().await?;
-
-                                        // ---- SEND HANDLE EARLY ----
-                                        if let Some(tx) = tx.take() {
-                                            let _ = tx.send(Ok((handle, port)));
-                                        }
-
-                                        if resolved_peers.len() > 0 {
-                                            // ---- RUN IBD AND AUTO RECONNECT ----
-                                            *node_state.is_syncing.write().await = true;
-
-                                            sleep(Duration::from_secs(2)).await;
-
-                                            let _ = start_auto_reconnect(node_state.clone(), blockchain.clone(), resolved_peers, false, std::thread::available_parallelism().unwrap().get());
-
-                                            println!("IBD status: {:?}", ibd_blockchain(node_state.clone(), blockchain, false, std::thread::available_parallelism().unwrap().get()).await);
-                                            let _ = synced_tx.send(());
-
-                                            *node_state.is_syncing.write().await = false;
-                                        }
-
-                                        // ---- KEEP NODE ALIVE ----
-                                        auto_peer.await?;
-
-                                        Ok(())
-                                    }
-                                    .await;
+                    if let Some(usage) = usage() {
+                        p {
+                            class: "text-sm text-neutral-400",
+                            "Blocks: {format_bytes(usage.blocks)} · Logs: {format_bytes(usage.logs)}"
+                            if let Some(free) = usage.free {
+                                " · Free: {format_bytes(free)}"
+                            }
+                        }
+                        if usage.low_space() {
+                            p {
+                                class: "text-sm text-yellow-400",
+                                "Less than {format_bytes(LOW_SPACE_BYTES)} left on this volume, the node will stop syncing once it is full."
+                            }
+                        }
+                    }
 
-                                    if let Err(e) = result {
-                                        if let Some(tx) = tx.take() {
-                                            let _ = tx.send(Err(e));
-                                        }
-                                    }
-                                });
-                            });
+                    label { "Hashing threads" }
+                    input {
+                        type: "number",
+                        min: "1",
+                        placeholder: "All cores",
+                        value: hashing_threads_setting,
+                        oninput: move |e| hashing_threads_setting.set(e.value())
+                    }
 
-                            // ---- UI SIDE ----
-                            spawn(async move {
-                                match rx.await {
-                                    Ok(Ok((handle, port))) => {
-                                        global.write().internal_node = Some(handle);
-                                        node_port.set(port.to_string());
+                    label { "API port" }
+                    input {
+                        type: "number",
+                        min: "{FIRST_UNPRIVILEGED_PORT}",
+                        max: "65535",
+                        placeholder: "Picked by the system",
+                        value: api_port_setting,
+                        oninput: move |e| api_port_setting.set(e.value())
+                    }
 
-                                        let client = loop {
-                                            match Client::connect(format!("127.0.0.1:{}", port).parse().unwrap()).await {
-                                                Ok(c) => break c,
-                                                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(200)).await,
-                                            }
-                                        };
+                    label {
+                        class: "flex items-center gap-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: connection_settings().node.api_bind == ApiBind::Lan,
+                            onchange: move |e| {
+                                connection_settings.write().node.api_bind = if e.checked() { ApiBind::Lan } else { ApiBind::Loopback };
+                            },
+                        }
+                        "Let other machines on the network use the API"
+                    }
+                    if connection_settings().node.api_bind == ApiBind::Lan {
+                        div {
+                            class: "flex items-center gap-2 text-sm",
+                            p {
+                                class: if connection_settings().api_access.require_token { "text-neutral-400" } else { "text-yellow-400" },
+                                if connection_settings().api_access.require_token {
+                                    "Other machines need an API token."
+                                } else {
+                                    "Anyone on the network can use the API."
+                                }
+                            }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                onclick: move |_| {
+                                    navigator.push(Route::ApiAccessSettings);
+                                },
+                                "Access control"
+                            }
+                        }
+                    }
 
-                                        global.write().api_client = Some(Arc::new(client));
-                                    }
-                                    Ok(Err(e)) => {
-                                        status.set(e.to_string());
-                                    }
+                    label {
+                        class: "flex items-center gap-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: connection_settings().node.full_ibd,
+                            onchange: move |e| connection_settings.write().node.full_ibd = e.checked(),
+                        }
+                        "Download and verify every block"
+                    }
 
-                                    Err(_) => {
-                                        status.set("Node thread closed unexpectedly".to_string());
-                                    }
-                                }
-                                match synced_rx.await {
-                                    Ok(()) => {
-                                        navigator.push(Route::Home);
-                                    },
-                                    Err(_) => {}
-                                }
-                            });
+                    button {
+                        disabled: node_running() || is_moving(),
+                        onclick: move |_| {
+                            auto_connecting.set(false);
+                            start_node();
                         },
                         "Start node"
                     }
 
                     {
-                        if started_node() {
-                            rsx! { p {
-                                b {
-                                    "Downloading historical blocks..."
-                                }
-                                br {}
-                                "Snap Coin API port: "
-                                b {
-                                    {node_port}
-                                }
-                            } }
+                        if node_running() {
+                            rsx! {
+                                NodeControls {}
+                                SyncProgressView {}
+                                BootstrapReport {}
+                                ApiAddress {}
+                            }
                         } else {
                             rsx! {}
                         }
@@ -198,13 +439,13 @@ pub fn Connection() -> Element {
                 }
 
                 {
-                    if !started_node() {
+                    if !node_running() {
                         rsx! {
                             div {
                                 class: "p-5 flex flex-col gap-5",
                                 h2 { class: "text-xl", "Connect to a running Node" }
 
-                                label { "Node API address" }
+                                label { "Node API addresses" }
 
                                 input {
                                     type: "text",
@@ -213,42 +454,19 @@ pub fn Connection() -> Element {
                                     oninput: move |e| external_api_setting.set(e.value())
                                 }
 
-                                button {
-                                    onclick: move |_| {
-                                        let addr = match external_api_setting().to_socket_addrs() {
-                                            Ok(mut addrs) => match addrs.next() {
-                                                Some(addr) => addr,
-                                                None => {
-                                                    status.set(format!("Could not resolve {}", external_api_setting()));
-                                                    return;
-                                                }
-                                            },
-                                            Err(_) => {
-                                                status.set(format!("Could not resolve {}", external_api_setting()));
-                                                return;
-                                            }
-                                        };
-
-                                        let mut status = status.clone();
-                                        let mut global = global.clone();
-                                        let navigator = navigator.clone();
+                                label { "API token" }
 
-                                        spawn(async move {
-                                            let client = Arc::new(match Client::connect(addr).await {
-                                                Ok(c) => c,
-                                                Err(e) => {
-                                                    status.set(e.to_string());
-                                                    return;
-                                                }
-                                            });
-
-                                            {
-                                                let mut g = global.write();
-                                                g.api_client = Some(client);
-                                            }
+                                input {
+                                    type: "password",
+                                    placeholder: "Only for nodes that require one",
+                                    value: external_token_setting,
+                                    oninput: move |e| external_token_setting.set(e.value())
+                                }
 
-                                            navigator.push(Route::Home);
-                                        });
+                                button {
+                                    onclick: move |_| {
+                                        auto_connecting.set(false);
+                                        connect_external();
                                     },
                                     "Connect"
                                 }
@@ -267,3 +485,241 @@ pub fn Connection() -> Element {
         }
     }
 }
+
+/// Lifecycle and sync progress of the in-wallet node, polled as both change on the node thread
+fn use_node_status() -> Signal<Option<(NodeLifecycle, SyncProgress)>> {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let mut status = use_signal(|| None);
+
+    use_future(move || async move {
+        loop {
+            let current = global
+                .peek()
+                .internal_node
+                .as_ref()
+                .map(|node| (node.lifecycle(), node.progress()));
+            if *status.peek() != current {
+                status.set(current);
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    });
+
+    status
+}
+
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Lifecycle of the in-wallet node, with stop and restart
+#[component]
+pub fn NodeControls() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let navigator = use_navigator();
+    let node_status = use_node_status();
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(|| "".to_string());
+
+    let Some((state, _)) = node_status() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div {
+            class: "flex items-center gap-2",
+            span {
+                class: match state {
+                    NodeLifecycle::Synced => "text-green-400",
+                    NodeLifecycle::Failed(_) => "text-red-400",
+                    _ => "text-neutral-400",
+                },
+                title: if let NodeLifecycle::Failed(e) = &state { "{e}" },
+                "Node: {state.label()}"
+            }
+            button {
+                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                disabled: is_busy() || state == NodeLifecycle::Stopping,
+                onclick: move |_| {
+                    spawn(async move {
+                        is_busy.set(true);
+                        stop_node(global).await;
+                        is_busy.set(false);
+                        navigator.replace(Route::Connection);
+                    });
+                },
+                "Stop"
+            }
+            button {
+                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                disabled: is_busy() || state == NodeLifecycle::Stopping,
+                onclick: move |_| {
+                    spawn(async move {
+                        is_busy.set(true);
+                        error.set("".to_string());
+                        if let Err(e) = restart_node(global).await {
+                            error.set(e);
+                        }
+                        is_busy.set(false);
+                    });
+                },
+                "Restart"
+            }
+            if !error().is_empty() {
+                span { class: "text-red-400 text-xs", "{error}" }
+            }
+        }
+    }
+}
+
+/// Height, speed and time left of the in-wallet node sync
+#[component]
+pub fn SyncProgressView() -> Element {
+    let node_status = use_node_status();
+
+    let Some((state, progress)) = node_status() else {
+        return rsx! {};
+    };
+    let percent = progress.fraction() * 100.0;
+    let best = progress
+        .best_peer_height
+        .map_or("unknown".to_string(), |best| best.to_string());
+    let eta = match progress.eta() {
+        Some(eta) => format_eta(eta),
+        None if progress.remaining() == 0 && progress.best_peer_height.is_some() => "Caught up".to_string(),
+        None => "Unknown".to_string(),
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2",
+            div {
+                class: "w-full h-2 bg-neutral-800 rounded overflow-hidden",
+                div {
+                    class: if state == NodeLifecycle::Synced { "h-full bg-green-500" } else { "h-full bg-blue-500 transition-all" },
+                    style: "width: {percent:.1}%",
+                }
+            }
+            div {
+                class: "grid grid-cols-[max-content_1fr] gap-x-4 gap-y-1 text-sm",
+                span { class: "text-neutral-500", "Height" }
+                span { class: "font-mono", "{progress.local_height} / {best} ({percent:.1}%)" }
+                span { class: "text-neutral-500", "Speed" }
+                span { class: "font-mono", "{progress.blocks_per_second:.1} blocks/s" }
+                span { class: "text-neutral-500", "Time left" }
+                span { class: "font-mono", "{eta}" }
+                span { class: "text-neutral-500", "Downloaded" }
+                span { class: "font-mono", "{format_bytes(progress.bytes_downloaded)}" }
+            }
+        }
+    }
+}
+
+/// Compact sync progress for the navigation bar, shown while the node is behind
+#[component]
+pub fn SyncIndicator() -> Element {
+    let node_status = use_node_status();
+
+    let Some((_, progress)) = node_status() else {
+        return rsx! {};
+    };
+    if progress.remaining() == 0 {
+        return rsx! {};
+    }
+    let percent = progress.fraction() * 100.0;
+    let eta = progress.eta().map(format_eta).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-1 w-28",
+            title: "{progress.local_height} / {progress.best_peer_height.unwrap_or_default()} blocks, {progress.blocks_per_second:.1} blocks/s",
+            span { class: "text-xs text-neutral-400 text-nowrap", "Sync {percent:.0}% {eta}" }
+            div {
+                class: "w-full h-1 bg-neutral-800 rounded overflow-hidden",
+                div { class: "h-full bg-blue-500 transition-all", style: "width: {percent:.1}%" }
+            }
+        }
+    }
+}
+
+/// How dialing each configured peer went, failed ones are retried in the background
+#[component]
+pub fn BootstrapReport() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let mut report: Signal<Vec<PeerReport>> = use_signal(Vec::new);
+    let mut retrying = use_signal(|| false);
+
+    use_future(move || async move {
+        loop {
+            let (current, running) = global
+                .peek()
+                .internal_node
+                .as_ref()
+                .map(|node| (node.supervisor.bootstrap_report(), !node.lifecycle().is_finished()))
+                .unwrap_or_default();
+            if *report.peek() != current {
+                report.set(current);
+            }
+            if *retrying.peek() != running {
+                retrying.set(running);
+            }
+            sleep(Duration::from_secs(1)).await;
+        }
+    });
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-1 text-sm",
+            for entry in report() {
+                div {
+                    class: "grid grid-cols-[1fr_2fr] gap-x-4",
+                    span { class: "font-mono truncate", "{entry.peer}" }
+                    span {
+                        class: match entry.outcome {
+                            PeerOutcome::Connected(_) => "text-green-400",
+                            PeerOutcome::Denied => "text-neutral-400",
+                            PeerOutcome::Failed(_) => "text-red-400",
+                        },
+                        "{entry.outcome.describe()}"
+                        if retrying() && matches!(entry.outcome, PeerOutcome::Failed(_)) {
+                            ", retrying"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where the API of the in-wallet node ended up listening
+#[component]
+fn ApiAddress() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let Some(supervisor) = global().internal_node.map(|node| node.supervisor) else {
+        return rsx! {};
+    };
+    let Some(address) = supervisor.api_address() else {
+        return rsx! {
+            p { "Snap Coin API: starting..." }
+        };
+    };
+    let configured = supervisor.config().options.api_port;
+
+    rsx! {
+        p {
+            "Snap Coin API: "
+            b { "{address}" }
+        }
+        if configured.is_some_and(|port| port != address.port()) {
+            p {
+                class: "text-sm text-yellow-400",
+                "Port {configured.unwrap_or_default()} was taken, the API listens on {address.port()} for now."
+            }
+        }
+    }
+}
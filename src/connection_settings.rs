@@ -0,0 +1,200 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{node_storage::default_node_dir, write_atomic};
+
+/// How the wallet reaches the network
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionMode {
+    /// Run a full node inside the wallet
+    InApp,
+    /// Use the API of a node running elsewhere
+    External,
+}
+
+/// Which interfaces the API of the in-wallet node listens on
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiBind {
+    /// Only this machine can reach the API
+    #[default]
+    Loopback,
+    /// Other machines on the network can reach the API
+    Lan,
+}
+
+impl ApiBind {
+    pub fn ip(&self) -> IpAddr {
+        match self {
+            ApiBind::Loopback => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            ApiBind::Lan => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        }
+    }
+}
+
+/// What a client holding an API token may do
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiPermission {
+    /// Query the chain, mempool and peers
+    ReadOnly,
+    /// Also submit transactions and blocks
+    Submit,
+}
+
+/// A token other machines use the API of the in-wallet node with
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ApiToken {
+    /// Who the token was handed to
+    pub name: String,
+    /// Sha256 of the token, which itself is only shown once
+    pub hash: String,
+    pub permission: ApiPermission,
+    pub created: i64,
+}
+
+/// Who may use the API of the in-wallet node from other machines, this machine always may
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct ApiAccess {
+    /// Other machines must present one of `tokens`, without one they may only read
+    pub require_token: bool,
+    pub tokens: Vec<ApiToken>,
+    /// Other machines allowed to connect at all, any when empty
+    pub allowed_ips: BTreeSet<IpAddr>,
+}
+
+/// Options of the in-wallet node
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct NodeOptions {
+    /// Download and verify every block instead of starting from the latest state
+    pub full_ibd: bool,
+    /// Threads used to hash blocks during initial block download, all cores when unset
+    pub hashing_threads: Option<usize>,
+    /// Where the chain and node logs are kept, `~/node-mainnet` when unset
+    pub data_dir: Option<PathBuf>,
+    /// Directory still holding the chain after `data_dir` was changed, until it is moved or left behind
+    pub pending_move: Option<PathBuf>,
+    /// Port the API listens on, one picked by the OS when unset or taken
+    pub api_port: Option<u16>,
+    /// Port the OS picked last time, tried again so the API keeps its address across runs
+    pub last_api_port: Option<u16>,
+    pub api_bind: ApiBind,
+}
+
+impl NodeOptions {
+    pub fn data_dir(&self) -> Result<PathBuf, Error> {
+        match &self.data_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => default_node_dir(),
+        }
+    }
+
+    /// The port the API tries first
+    pub fn preferred_api_port(&self) -> Option<u16> {
+        self.api_port.or(self.last_api_port)
+    }
+
+    pub fn hashing_threads(&self) -> usize {
+        self.hashing_threads
+            .filter(|threads| *threads > 0)
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|threads| threads.get())
+                    .unwrap_or(1)
+            })
+    }
+}
+
+/// What the `Connection` screen was last set up with
+/// Kept as plain JSON, it is needed before a node is running and holds no keys
+/// API tokens handed out are kept as hashes, the token for external APIs is kept with the `Settings`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct ConnectionSettings {
+    /// Mode of the last successful connection, none until the wallet connected once
+    pub last_mode: Option<ConnectionMode>,
+    /// Reconnect with `last_mode` right after unlocking
+    pub auto_connect: bool,
+    /// Peers the in-wallet node connects to first
+    pub peers: Vec<String>,
+    /// External node APIs, tried in order
+    pub external_apis: Vec<String>,
+    pub node: NodeOptions,
+    /// Peers the in-wallet node keeps connected
+    pub allowed_peers: Vec<String>,
+    /// Addresses the in-wallet node never connects to
+    pub denied_peers: BTreeSet<IpAddr>,
+    pub api_access: ApiAccess,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        ConnectionSettings {
+            last_mode: None,
+            auto_connect: true,
+            peers: vec![
+                "node.snap-coin.net:8998".to_string(),
+                "node.snap-coin.net:7887".to_string(),
+            ],
+            external_apis: vec!["127.0.0.1:3003".to_string()],
+            node: NodeOptions::default(),
+            allowed_peers: vec![],
+            denied_peers: BTreeSet::new(),
+            api_access: ApiAccess::default(),
+        }
+    }
+}
+
+/// Split a comma separated list of addresses, as typed in the `Connection` screen
+pub fn parse_address_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns connection settings file path
+pub fn connection_settings_path() -> Result<PathBuf, Error> {
+    let home = dirs::home_dir().ok_or_else(|| Error::msg("Could not determine home directory"))?;
+    Ok(home.join(".snap-coin-wallet-connection.json"))
+}
+
+/// Load the connection settings, falling back to defaults when none were saved yet
+/// A file that does not parse is moved aside first, so saving the defaults does not overwrite it
+pub fn load_connection_settings() -> Result<ConnectionSettings, Error> {
+    let path = connection_settings_path()?;
+    if !path.exists() {
+        return Ok(ConnectionSettings::default());
+    }
+    match serde_json::from_slice(&fs::read(&path)?) {
+        Ok(settings) => Ok(settings),
+        Err(e) => {
+            let mut backup = path.clone().into_os_string();
+            backup.push(".bak");
+            let backup = PathBuf::from(backup);
+            fs::rename(&path, &backup)?;
+            Err(anyhow!(
+                "{e}, the file was kept as {} and defaults are used",
+                backup.display()
+            ))
+        }
+    }
+}
+
+pub fn save_connection_settings(settings: &ConnectionSettings) -> Result<(), Error> {
+    write_atomic(
+        &connection_settings_path()?,
+        &serde_json::to_vec_pretty(settings)?,
+    )
+}
@@ -32,7 +32,9 @@ pub fn CopyBox(
                 ),
                 src: if copied() { COPY_DONE_ICON } else { COPY_ICON },
 
-                onclick: move |_| {
+                onclick: move |e| {
+                    // Copying should not also trigger the onclick of the box
+                    e.stop_propagation();
                     copied.set(true);
                     rotating.set(true);
 
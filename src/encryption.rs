@@ -9,6 +9,31 @@ fn compute_pin_hash(pin: &str) -> [u8; 32] {
     Hash::new(format!("snap-coin-wallet-{}", pin).as_bytes()).dump_buf()
 }
 
+/// Encrypt arbitrary data using a PIN
+/// Serialized as: [nonce(12 bytes)|ciphertext]
+pub fn encrypt_bytes(data: &[u8], pin: &str) -> Option<Vec<u8>> {
+    let cipher = Aes256Gcm::new_from_slice(&compute_pin_hash(pin)).ok()?;
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher.encrypt(nonce, data).ok()?;
+    let mut out = Vec::with_capacity(12 + ciphertext.len());
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    Some(out)
+}
+
+/// Decrypt data encrypted with `encrypt_bytes`
+pub fn decrypt_bytes(data: &[u8], pin: &str) -> Option<Vec<u8>> {
+    if data.len() < 12 {
+        return None;
+    }
+    let cipher = Aes256Gcm::new_from_slice(&compute_pin_hash(pin)).ok()?;
+    let nonce = Nonce::from_slice(&data[..12]);
+    cipher.decrypt(nonce, &data[12..]).ok()
+}
+
 /// Encrypt multiple wallets using a PIN
 /// Serialized as: [name_len(u8)|name|private_key(32 bytes)] repeated
 pub fn encrypt_wallets(wallets: &HashMap<String, Private>, pin: &str) -> Option<Vec<u8>> {
@@ -23,27 +48,12 @@ pub fn encrypt_wallets(wallets: &HashMap<String, Private>, pin: &str) -> Option<
         serialized.extend_from_slice(key.dump_buf());
     }
 
-    let cipher = Aes256Gcm::new_from_slice(&compute_pin_hash(pin)).ok()?;
-    let mut nonce_bytes = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce_bytes);
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    let ciphertext = cipher.encrypt(nonce, serialized.as_ref()).ok()?;
-    let mut out = Vec::with_capacity(12 + ciphertext.len());
-    out.extend_from_slice(&nonce_bytes);
-    out.extend_from_slice(&ciphertext);
-    Some(out)
+    encrypt_bytes(&serialized, pin)
 }
 
 /// Decrypt multiple wallets using a PIN
 pub fn decrypt_wallets(data: &[u8], pin: &str) -> Option<HashMap<String, Private>> {
-    if data.len() < 12 {
-        return None;
-    }
-    let cipher = Aes256Gcm::new_from_slice(&compute_pin_hash(pin)).ok()?;
-    let nonce = Nonce::from_slice(&data[..12]);
-    let ciphertext = &data[12..];
-    let decrypted = cipher.decrypt(nonce, ciphertext.as_ref()).ok()?;
+    let decrypted = decrypt_bytes(data, pin)?;
 
     let mut wallets = HashMap::new();
     let mut i = 0;
@@ -0,0 +1,777 @@
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use snap_coin::{
+    api::client::Client,
+    blockchain_data_provider::BlockchainDataProvider,
+    core::{
+        block::Block,
+        block_store::TransactionAndInfo,
+        transaction::{Transaction, TransactionId},
+    },
+    crypto::{keys::Public, Hash},
+    to_snap,
+};
+
+use crate::{
+    chain::{input_amount, ChainError},
+    copy_box::CopyBox,
+    home::{fetch_history, format_timestamp_secs, HistoryTX},
+    sync::SyncState,
+    GlobalContext, Route,
+};
+
+/// How many of the latest transactions the address view shows
+const ADDRESS_HISTORY: usize = 20;
+
+/// How many blocks one page of the explorer lists
+const BLOCKS_PER_PAGE: usize = 20;
+
+type ChainProvider = Arc<dyn BlockchainDataProvider + Send + Sync>;
+
+/// Reads blocks straight from the in-wallet node when it runs, so browsing never leaves the machine
+fn chain_provider(global: &GlobalContext) -> Option<ChainProvider> {
+    if let Some(node) = &global.internal_node {
+        return Some(node.blockchain.clone());
+    }
+    global
+        .api_client
+        .clone()
+        .map(|client| client as ChainProvider)
+}
+
+fn total_out(transaction: &Transaction) -> u64 {
+    transaction.outputs.iter().map(|output| output.amount).sum()
+}
+
+/// Address that opens the in-app address view when clicked, labeled with the wallet name if it is one of ours
+#[component]
+pub fn AddressLink(address: Public, #[props(optional)] class: String) -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let label = global().wallet_names().get(&address).cloned();
+
+    rsx! {
+        div {
+            class: "flex items-center gap-2 min-w-0 cursor-pointer {class}",
+            if let Some(label) = label {
+                span { class: "font-bold text-nowrap", "{label}" }
+            }
+            CopyBox {
+                class: "w-full min-w-0",
+                text: address.dump_base36(),
+                title: "Open address",
+                onclick: move |_| {
+                    navigator().push(Route::AddressView { address: address.dump_base36() });
+                }
+            }
+        }
+    }
+}
+
+/// Transaction id that opens the in-app transaction detail when clicked
+#[component]
+pub fn TransactionLink(transaction_id: TransactionId, #[props(optional)] class: String) -> Element {
+    rsx! {
+        CopyBox {
+            class: "min-w-0 cursor-pointer {class}",
+            text: transaction_id.dump_base36(),
+            title: "Open transaction",
+            onclick: move |_| {
+                navigator().push(Route::TransactionDetail { id: transaction_id.dump_base36() });
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TransactionDetails {
+    info: TransactionAndInfo,
+    input_amounts: Vec<Option<u64>>,
+    height: usize,
+}
+
+#[component]
+pub fn TransactionDetail(id: String) -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+    if global().api_client.is_none() {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    }
+    let client = global().api_client.unwrap();
+
+    let mut details: Signal<Option<TransactionDetails>> = use_signal(|| None);
+    let mut error = use_signal(|| "".to_string());
+    let mut retry = use_signal(|| 0u32);
+
+    // Reload on retry, and when the chain moved so confirmations stay current
+    use_effect(use_reactive!(|id| {
+        retry();
+        let _ = sync.read().revision;
+        let client = client.clone();
+        spawn(async move {
+            let syncing = sync.peek().syncing;
+            let result = async {
+                let transaction_id = TransactionId::new_from_base36(&id)
+                    .ok_or_else(|| ChainError::Node("Invalid transaction id".to_string()))?;
+                let info = client
+                    .get_transaction_and_info(&transaction_id)
+                    .await?
+                    .ok_or_else(|| ChainError::missing(&transaction_id))?;
+
+                let mut input_amounts = vec![];
+                for input in &info.transaction.inputs {
+                    match input_amount(&client, input).await {
+                        Ok(amount) => input_amounts.push(Some(amount)),
+                        Err(ChainError::ConnectionLost) => return Err(ChainError::ConnectionLost),
+                        Err(_) => input_amounts.push(None),
+                    }
+                }
+
+                Ok(TransactionDetails {
+                    info,
+                    input_amounts,
+                    height: client.get_height().await?,
+                })
+            }
+            .await;
+
+            match result {
+                Ok(result) => {
+                    details.set(Some(result));
+                    error.set("".into());
+                }
+                Err(e) => error.set(e.or_syncing(syncing).to_string()),
+            }
+        });
+    }));
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+
+            div {
+                class: "flex items-center gap-5",
+                button {
+                    class: "bg-transparent! border! border-[var(--border)]!",
+                    onclick: move |_| navigator().go_back(),
+                    "Back"
+                }
+                h1 { class: "text-2xl font-bold text-nowrap", "Transaction" }
+            }
+
+            if let Some(details) = details() {
+                {
+                    let transaction = details.info.transaction.clone();
+                    let confirmations = (details.height as u64).saturating_sub(details.info.at_height);
+                    let total = total_out(&transaction);
+
+                    rsx! {
+                        div {
+                            class: "bg-neutral-900 rounded-xl p-6 shadow grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                            span { class: "text-neutral-500", "Transaction ID" }
+                            CopyBox { class: "w-full min-w-0", text: id.clone(), title: "Transaction ID" }
+                            span { class: "text-neutral-500", "Block" }
+                            BlockLink { class: "w-full", hash: details.info.in_block }
+                            span { class: "text-neutral-500", "Height" }
+                            span { class: "font-mono", "#{details.info.at_height}" }
+                            span { class: "text-neutral-500", "Confirmations" }
+                            span { class: "font-mono", "{confirmations}" }
+                            span { class: "text-neutral-500", "Time" }
+                            span { "{format_timestamp_secs(transaction.timestamp)}" }
+                            span { class: "text-neutral-500", "Total" }
+                            span { class: "font-mono", "{to_snap(total)} SNAP" }
+                        }
+
+                        div {
+                            class: "grid grid-cols-2 gap-6 flex-1 min-h-0",
+
+                            div {
+                                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-2 overflow-auto",
+                                h3 { class: "font-semibold text-lg mb-2", "Inputs" }
+                                if transaction.inputs.is_empty() {
+                                    p { class: "text-neutral-400", "Newly minted, no inputs" }
+                                }
+                                for (input, amount) in transaction.inputs.iter().zip(details.input_amounts.iter()) {
+                                    div {
+                                        class: "bg-neutral-800 p-4 rounded flex flex-col gap-2 text-sm",
+                                        div {
+                                            class: "flex items-center justify-between gap-5",
+                                            AddressLink { class: "flex-1", address: input.output_owner }
+                                            span {
+                                                class: "font-mono font-bold text-red-400 text-nowrap",
+                                                match amount {
+                                                    Some(amount) => format!("-{} SNAP", to_snap(*amount)),
+                                                    None => "Unknown".to_string(),
+                                                }
+                                            }
+                                        }
+                                        div {
+                                            class: "flex items-center gap-2 text-neutral-500 text-xs",
+                                            span { class: "text-nowrap", "Funded by output {input.output_index} of" }
+                                            TransactionLink { class: "flex-1", transaction_id: input.transaction_id }
+                                        }
+                                    }
+                                }
+                            }
+
+                            div {
+                                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-2 overflow-auto",
+                                h3 { class: "font-semibold text-lg mb-2", "Outputs" }
+                                for output in transaction.outputs.iter() {
+                                    div {
+                                        class: "bg-neutral-800 p-4 rounded flex items-center justify-between gap-5 text-sm",
+                                        AddressLink { class: "flex-1", address: output.receiver }
+                                        span { class: "font-mono font-bold text-green-400 text-nowrap", "+{to_snap(output.amount)} SNAP" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{error}" }
+                    button {
+                        onclick: move |_| retry += 1,
+                        "Retry"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn AddressView(address: String) -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+    if global().api_client.is_none() {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    }
+    let client = global().api_client.unwrap();
+
+    let mut balance: Signal<Option<u64>> = use_signal(|| None);
+    let mut history: Signal<Vec<HistoryTX>> = use_signal(Vec::new);
+    let mut error = use_signal(|| "".to_string());
+    let mut retry = use_signal(|| 0u32);
+
+    let public = Public::new_from_base36(&address);
+    let label = public.and_then(|public| global().wallet_names().get(&public).cloned());
+
+    use_effect(use_reactive!(|address| {
+        retry();
+        let _ = sync.read().revision;
+        let client = client.clone();
+        spawn(async move {
+            let syncing = sync.peek().syncing;
+            let result = async {
+                let public = Public::new_from_base36(&address)
+                    .ok_or_else(|| ChainError::Node("Invalid address".to_string()))?;
+                let address_balance = client.get_balance(public).await?;
+                let vault = global.peek().wallet_names();
+                let (entries, errors) =
+                    fetch_history(&client, public, Some(ADDRESS_HISTORY), &vault).await?;
+                Ok::<_, ChainError>((address_balance, entries, errors))
+            }
+            .await;
+
+            match result {
+                Ok((address_balance, entries, errors)) => {
+                    balance.set(Some(address_balance));
+                    history.set(entries);
+                    match errors.into_iter().next() {
+                        Some(e) => error.set(format!(
+                            "Some transactions could not be loaded: {}",
+                            e.or_syncing(syncing)
+                        )),
+                        None => error.set("".into()),
+                    }
+                }
+                Err(e) => error.set(e.or_syncing(syncing).to_string()),
+            }
+        });
+    }));
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+
+            div {
+                class: "flex items-center gap-5",
+                button {
+                    class: "bg-transparent! border! border-[var(--border)]!",
+                    onclick: move |_| navigator().go_back(),
+                    "Back"
+                }
+                h1 { class: "text-2xl font-bold text-nowrap", "Address" }
+                if let Some(label) = label {
+                    span { class: "text-neutral-400", "{label}" }
+                }
+            }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-2",
+                CopyBox { class: "w-full min-w-0", text: address.clone(), title: "Address" }
+                p { class: "text-sm text-neutral-400 mt-2", "Balance" }
+                h2 {
+                    class: "text-3xl font-bold font-mono",
+                    match balance() {
+                        Some(balance) => format!("{} SNAP", to_snap(balance)),
+                        None => "...".to_string(),
+                    }
+                }
+            }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col overflow-hidden flex-1",
+                h3 { class: "font-semibold text-lg mb-4", "Latest transactions" }
+
+                div {
+                    class: "flex flex-col gap-2 overflow-auto pr-2",
+                    if history.read().is_empty() {
+                        p { class: "text-neutral-400", "No transactions" }
+                    }
+                    for tx in history.read().iter() {
+                        {
+                            let amount_class = if tx.is_send { "text-red-400" } else { "text-green-400" };
+                            let sign = if tx.is_send { "-" } else { "+" };
+
+                            rsx! {
+                                div {
+                                    key: "{tx.tx.dump_base36()}",
+                                    class: "bg-neutral-800 p-4 rounded grid grid-cols-[1fr_max-content] items-center gap-x-4 text-sm",
+                                    TransactionLink { class: "w-full", transaction_id: tx.tx }
+                                    div {
+                                        class: "flex flex-col items-end",
+                                        span { class: "font-bold font-mono {amount_class}", "{sign}{tx.amount_snap:.4} SNAP" }
+                                        span { class: "text-neutral-500 text-xs whitespace-nowrap", "{tx.when}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{error}" }
+                    button {
+                        onclick: move |_| retry += 1,
+                        "Retry"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Block hash that opens the in-app block detail when clicked
+#[component]
+pub fn BlockLink(hash: Hash, #[props(optional)] class: String) -> Element {
+    rsx! {
+        CopyBox {
+            class: "min-w-0 cursor-pointer {class}",
+            text: hash.dump_base36(),
+            title: "Open block",
+            onclick: move |_| {
+                navigator().push(Route::BlockDetail { hash: hash.dump_base36() });
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct BlockSummary {
+    height: usize,
+    hash: Hash,
+    timestamp: u64,
+    transactions: usize,
+    total: u64,
+}
+
+impl BlockSummary {
+    fn new(height: usize, block: &Block) -> Option<Self> {
+        Some(BlockSummary {
+            height,
+            hash: block.meta.hash?,
+            timestamp: block.timestamp,
+            transactions: block.transactions.len(),
+            total: block.transactions.iter().map(total_out).sum(),
+        })
+    }
+}
+
+/// Find what a search query refers to: a block height, a block hash, a transaction id, or an address
+async fn resolve_search(
+    query: &str,
+    provider: &ChainProvider,
+    client: &Client,
+) -> Result<Option<Route>, ChainError> {
+    if let Ok(height) = query.parse::<usize>() {
+        return Ok(provider
+            .get_block_hash_by_height(height)
+            .await?
+            .map(|hash| Route::BlockDetail {
+                hash: hash.dump_base36(),
+            }));
+    }
+    if let Some(hash) = Hash::new_from_base36(query) {
+        if provider.get_height_by_hash(hash).await?.is_some() {
+            return Ok(Some(Route::BlockDetail {
+                hash: query.to_string(),
+            }));
+        }
+        if client.get_transaction(&hash).await?.is_some() {
+            return Ok(Some(Route::TransactionDetail {
+                id: query.to_string(),
+            }));
+        }
+    }
+    if Public::new_from_base36(query).is_some() {
+        return Ok(Some(Route::AddressView {
+            address: query.to_string(),
+        }));
+    }
+    Ok(None)
+}
+
+#[component]
+pub fn Explorer() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+    let Some(provider) = chain_provider(&global()) else {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    };
+    let Some(client) = global().api_client else {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    };
+
+    let mut blocks: Signal<Vec<BlockSummary>> = use_signal(Vec::new);
+    // Height of the newest block on the page, None follows the chain tip
+    let mut page_top: Signal<Option<usize>> = use_signal(|| None);
+    let mut tip = use_signal(|| 0usize);
+    let mut error = use_signal(|| "".to_string());
+    let mut retry = use_signal(|| 0u32);
+
+    let mut query = use_signal(|| "".to_string());
+    let mut searching = use_signal(|| false);
+    let mut search_status = use_signal(|| "".to_string());
+
+    let list_provider = provider.clone();
+    use_effect(move || {
+        retry();
+        let _ = sync.read().revision;
+        let top = page_top();
+        let provider = list_provider.clone();
+        spawn(async move {
+            let syncing = sync.peek().syncing;
+            let result = async {
+                let height = provider.get_height().await?;
+                let newest = top.unwrap_or(height.saturating_sub(1));
+                let mut page = vec![];
+                for block_height in (newest.saturating_sub(BLOCKS_PER_PAGE - 1)..=newest).rev() {
+                    if let Some(block) = provider.get_block_by_height(block_height).await? {
+                        page.extend(BlockSummary::new(block_height, &block));
+                    }
+                }
+                Ok::<_, ChainError>((height, page))
+            }
+            .await;
+
+            match result {
+                Ok((height, page)) => {
+                    tip.set(height);
+                    blocks.set(page);
+                    error.set("".into());
+                }
+                Err(e) => error.set(e.or_syncing(syncing).to_string()),
+            }
+        });
+    });
+
+    let search = use_callback(move |_: ()| {
+        let query = query().trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        let provider = provider.clone();
+        let client = client.clone();
+        spawn(async move {
+            searching.set(true);
+            match resolve_search(&query, &provider, &client).await {
+                Ok(Some(route)) => {
+                    search_status.set("".into());
+                    navigator().push(route);
+                }
+                Ok(None) => search_status.set(format!("Nothing found for {query}")),
+                Err(e) => search_status.set(e.to_string()),
+            }
+            searching.set(false);
+        });
+    });
+
+    let newest_shown = blocks.read().first().map(|b| b.height);
+    let oldest_shown = blocks.read().last().map(|b| b.height);
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+
+            div {
+                class: "flex items-center justify-between gap-5",
+                h1 { class: "text-2xl font-bold text-nowrap", "Explorer" }
+                span {
+                    class: "text-sm text-neutral-400",
+                    if global().internal_node.is_some() { "Reading from the in-wallet node" } else { "Reading from the connected node" }
+                }
+            }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex gap-4 items-center",
+                input {
+                    class: "flex-1 font-mono",
+                    value: "{query}",
+                    oninput: move |e| query.set(e.value()),
+                    onkeydown: move |e| {
+                        if e.key() == Key::Enter {
+                            search(());
+                        }
+                    },
+                    placeholder: "Block height, block hash, transaction id or address..."
+                }
+                button {
+                    disabled: searching(),
+                    onclick: move |_| search(()),
+                    "Search"
+                }
+            }
+            if !search_status().is_empty() {
+                p { class: "text-neutral-400", "{search_status}" }
+            }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col overflow-hidden flex-1",
+
+                div {
+                    class: "flex items-center justify-between mb-4",
+                    h3 { class: "font-semibold text-lg", "Blocks" }
+                    span { class: "text-sm text-neutral-400", "Chain height {tip}" }
+                }
+
+                div {
+                    class: "flex flex-col gap-2 overflow-auto pr-2",
+                    for block in blocks.read().iter() {
+                        div {
+                            key: "{block.height}",
+                            class: "bg-neutral-800 p-4 rounded grid grid-cols-[max-content_1fr_max-content] items-center gap-x-4 text-sm",
+                            span { class: "font-mono font-bold", "#{block.height}" }
+                            BlockLink { class: "w-full", hash: block.hash }
+                            div {
+                                class: "flex flex-col items-end",
+                                span { class: "font-mono", "{block.transactions} transactions · {to_snap(block.total):.4} SNAP" }
+                                span { class: "text-neutral-500 text-xs whitespace-nowrap", "{format_timestamp_secs(block.timestamp)}" }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex gap-4 mt-4",
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: page_top().is_none(),
+                        onclick: move |_| page_top.set(None),
+                        "Latest"
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: newest_shown.is_none_or(|newest| newest + 1 >= tip()),
+                        onclick: move |_| {
+                            if let Some(newest) = newest_shown {
+                                let top = newest + BLOCKS_PER_PAGE;
+                                page_top.set(if top + 1 >= tip() { None } else { Some(top) });
+                            }
+                        },
+                        "Newer"
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: oldest_shown.is_none_or(|oldest| oldest == 0),
+                        onclick: move |_| {
+                            if let Some(oldest) = oldest_shown {
+                                page_top.set(Some(oldest.saturating_sub(1)));
+                            }
+                        },
+                        "Older"
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{error}" }
+                    button {
+                        onclick: move |_| retry += 1,
+                        "Retry"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct BlockDetails {
+    block: Block,
+    height: usize,
+    tip: usize,
+}
+
+#[component]
+pub fn BlockDetail(hash: String) -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+    let Some(provider) = chain_provider(&global()) else {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    };
+
+    let mut details: Signal<Option<BlockDetails>> = use_signal(|| None);
+    let mut error = use_signal(|| "".to_string());
+    let mut retry = use_signal(|| 0u32);
+
+    use_effect(use_reactive!(|hash| {
+        retry();
+        let _ = sync.read().revision;
+        let provider = provider.clone();
+        spawn(async move {
+            let syncing = sync.peek().syncing;
+            let result = async {
+                let block_hash = Hash::new_from_base36(&hash)
+                    .ok_or_else(|| ChainError::Node("Invalid block hash".to_string()))?;
+                let block = provider.get_block_by_hash(block_hash).await?;
+                let height = provider.get_height_by_hash(block_hash).await?;
+                let (Some(block), Some(height)) = (block, height) else {
+                    return Err(ChainError::Node(format!("Block {hash} was not found")));
+                };
+                Ok(BlockDetails {
+                    block,
+                    height,
+                    tip: provider.get_height().await?,
+                })
+            }
+            .await;
+
+            match result {
+                Ok(result) => {
+                    details.set(Some(result));
+                    error.set("".into());
+                }
+                Err(e) => error.set(e.or_syncing(syncing).to_string()),
+            }
+        });
+    }));
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+
+            div {
+                class: "flex items-center gap-5",
+                button {
+                    class: "bg-transparent! border! border-[var(--border)]!",
+                    onclick: move |_| navigator().go_back(),
+                    "Back"
+                }
+                h1 { class: "text-2xl font-bold text-nowrap", "Block" }
+                if let Some(details) = details() {
+                    span { class: "text-neutral-400 font-mono", "#{details.height}" }
+                }
+            }
+
+            if let Some(details) = details() {
+                {
+                    let block = details.block.clone();
+                    let confirmations = details.tip.saturating_sub(details.height);
+                    let total: u64 = block.transactions.iter().map(total_out).sum();
+
+                    rsx! {
+                        div {
+                            class: "bg-neutral-900 rounded-xl p-6 shadow grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                            span { class: "text-neutral-500", "Hash" }
+                            CopyBox { class: "w-full min-w-0", text: hash.clone(), title: "Block hash" }
+                            span { class: "text-neutral-500", "Previous block" }
+                            if details.height > 0 {
+                                BlockLink { class: "w-full", hash: block.meta.previous_block }
+                            } else {
+                                span { "None, this is the genesis block" }
+                            }
+                            span { class: "text-neutral-500", "Height" }
+                            span { class: "font-mono", "#{details.height}" }
+                            span { class: "text-neutral-500", "Confirmations" }
+                            span { class: "font-mono", "{confirmations}" }
+                            span { class: "text-neutral-500", "Time" }
+                            span { "{format_timestamp_secs(block.timestamp)}" }
+                            span { class: "text-neutral-500", "Nonce" }
+                            span { class: "font-mono", "{block.nonce}" }
+                            span { class: "text-neutral-500", "Total" }
+                            span { class: "font-mono", "{to_snap(total)} SNAP" }
+                        }
+
+                        div {
+                            class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col overflow-hidden flex-1",
+                            h3 { class: "font-semibold text-lg mb-4", "{block.transactions.len()} transactions" }
+
+                            div {
+                                class: "flex flex-col gap-2 overflow-auto pr-2",
+                                for transaction in block.transactions.iter() {
+                                    div {
+                                        class: "bg-neutral-800 p-4 rounded grid grid-cols-[1fr_max-content] items-center gap-x-4 text-sm",
+                                        if let Some(transaction_id) = transaction.transaction_id {
+                                            TransactionLink { class: "w-full", transaction_id }
+                                        } else {
+                                            span { "No transaction id" }
+                                        }
+                                        div {
+                                            class: "flex flex-col items-end",
+                                            span { class: "font-mono font-bold", "{to_snap(total_out(transaction)):.4} SNAP" }
+                                            span {
+                                                class: "text-neutral-500 text-xs whitespace-nowrap",
+                                                if transaction.inputs.is_empty() {
+                                                    "Block reward"
+                                                } else {
+                                                    "{transaction.inputs.len()} inputs · {transaction.outputs.len()} outputs"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{error}" }
+                    button {
+                        onclick: move |_| retry += 1,
+                        "Retry"
+                    }
+                }
+            }
+        }
+    }
+}
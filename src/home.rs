@@ -1,37 +1,184 @@
-use std::time::Duration;
+use std::{collections::HashMap, fs};
 
 use anyhow::anyhow;
 use chrono::{Local, TimeZone};
 use dioxus::prelude::*;
+use rfd::FileDialog;
 use snap_coin::{
-    blockchain_data_provider::{BlockchainDataProvider, BlockchainDataProviderError},
+    api::client::Client,
+    blockchain_data_provider::BlockchainDataProvider,
     build_transaction,
-    core::transaction::{TransactionId, MAX_TRANSACTION_IO},
+    core::transaction::{Transaction, TransactionId, MAX_TRANSACTION_IO},
     crypto::keys::{Private, Public},
     economics::DEV_WALLET,
     to_nano, to_snap, UtilError,
 };
-use tokio::time::sleep;
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{
-    annotate::AnnotateTransaction, authorize::ask_for_auth, copy_box::CopyBox, GlobalContext, Route,
+    annotate::AnnotateTransaction,
+    annotations::Annotations,
+    authorize::ask_for_auth,
+    chain::{input_amount, require_transaction, ChainError},
+    copy_box::CopyBox,
+    sync::{request_sync, SyncEvent, SyncState},
+    GlobalContext, Route,
 };
 
 const REFRESH: Asset = asset!("../assets/refresh.svg");
 const FILE_CHECK: Asset = asset!("../assets/file_check.svg");
+/// Pages of transaction ids a history of limited length is taken from
+const HISTORY_PAGES: u32 = 2;
 
 pub fn format_timestamp_secs(ts: u64) -> String {
     let dt = Local.timestamp_opt(ts as i64, 0).unwrap();
     dt.format("%b %d, %Y · %H:%M:%S").to_string()
 }
 
-struct HistoryTX {
-    receivers: Vec<Public>,
-    senders: Vec<Public>,
-    is_send: bool,
-    amount_snap: f64,
-    tx: TransactionId,
-    when: String,
+#[derive(Clone)]
+pub struct HistoryTX {
+    pub receivers: Vec<Public>,
+    pub senders: Vec<Public>,
+    pub is_send: bool,
+    pub amount_snap: f64,
+    pub tx: TransactionId,
+    pub timestamp: u64,
+    pub when: String,
+    /// Name of the other vault wallet, if this was a transfer between our own wallets
+    pub internal_with: Option<String>,
+}
+
+/// Fetches the latest `count` transactions of an address, or all of them when none, and resolves how much each moved in or out of it
+/// `vault` maps the addresses of our own wallets to their names, and is used to detect internal transfers
+/// Transactions that could not be resolved are left out, and their errors returned next to the partial history
+pub async fn fetch_history(
+    client: &Client,
+    public: Public,
+    count: Option<usize>,
+    vault: &HashMap<Public, String>,
+) -> Result<(Vec<HistoryTX>, Vec<ChainError>), ChainError> {
+    let tx_ids = client
+        .get_transactions_of_address(public, count.map(|_| HISTORY_PAGES))
+        .await?;
+
+    let mut history = vec![];
+    let mut errors = vec![];
+
+    for tx_id in tx_ids.iter().take(count.unwrap_or(usize::MAX)) {
+        match resolve_history_tx(client, public, *tx_id, vault).await {
+            Ok(entry) => history.push(entry),
+            Err(ChainError::ConnectionLost) => return Err(ChainError::ConnectionLost),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Ok((history, errors))
+}
+
+/// How much a transaction paid to an address, and how much it spent from it
+pub async fn address_flows(
+    client: &Client,
+    public: Public,
+    tx: &Transaction,
+) -> Result<(u64, u64), ChainError> {
+    let mut spent = 0;
+    for input in &tx.inputs {
+        if input.output_owner == public {
+            spent += input_amount(client, input).await?;
+        }
+    }
+
+    let received = tx
+        .outputs
+        .iter()
+        .filter(|output| output.receiver == public)
+        .map(|output| output.amount)
+        .sum();
+
+    Ok((received, spent))
+}
+
+async fn resolve_history_tx(
+    client: &Client,
+    public: Public,
+    tx_id: TransactionId,
+    vault: &HashMap<Public, String>,
+) -> Result<HistoryTX, ChainError> {
+    let tx = require_transaction(client, &tx_id).await?;
+    let (my_in, my_out) = address_flows(client, public, &tx).await?;
+
+    // A transfer is internal when every party of it is one of our own wallets
+    let internal_with = if tx.inputs.iter().all(|i| vault.contains_key(&i.output_owner))
+        && tx.outputs.iter().all(|o| vault.contains_key(&o.receiver))
+    {
+        let counterparty = if my_in < my_out {
+            tx.outputs.iter().map(|o| o.receiver).find(|r| *r != public)
+        } else {
+            tx.inputs.iter().map(|i| i.output_owner).find(|s| *s != public)
+        };
+        counterparty.map(|c| vault[&c].clone())
+    } else {
+        None
+    };
+
+    Ok(HistoryTX {
+        senders: tx.inputs.iter().map(|i| i.output_owner).collect(),
+        receivers: tx.outputs.iter().map(|o| o.receiver).collect(),
+        is_send: my_in < my_out,
+        amount_snap: to_snap((my_out as i64 - my_in as i64).unsigned_abs()),
+        tx: tx_id,
+        timestamp: tx.timestamp,
+        when: format_timestamp_secs(tx.timestamp),
+        internal_with,
+    })
+}
+
+/// Quotes a CSV field when it has to be
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// History as CSV, one row per transaction next to the title it was annotated with
+fn history_csv(history: &[HistoryTX], annotations: &Annotations) -> String {
+    let mut csv = "Time,Transaction ID,Direction,Amount (SNAP),Counterparty,Title\n".to_string();
+    for tx in history {
+        let direction = match (&tx.internal_with, tx.is_send) {
+            (Some(_), true) => "Internal transfer out",
+            (Some(_), false) => "Internal transfer in",
+            (None, true) => "Sent",
+            (None, false) => "Received",
+        };
+        let counterparty = tx.internal_with.clone().unwrap_or_else(|| {
+            let first = if tx.is_send {
+                tx.receivers.first()
+            } else {
+                tx.senders.first()
+            };
+            first.map_or("network".to_string(), |p| p.dump_base36())
+        });
+        let time = Local
+            .timestamp_opt(tx.timestamp as i64, 0)
+            .single()
+            .map_or(tx.timestamp.to_string(), |t| t.to_rfc3339());
+        let title = annotations.get(&tx.tx).map_or("", |a| a.title.as_str());
+        let amount = format!("{}{}", if tx.is_send { "-" } else { "" }, tx.amount_snap);
+
+        let row = [
+            time,
+            tx.tx.dump_base36(),
+            direction.to_string(),
+            amount,
+            counterparty,
+            title.to_string(),
+        ];
+        csv += &row.map(|field| csv_field(&field)).join(",");
+        csv += "\n";
+    }
+    csv
 }
 
 #[component]
@@ -41,6 +188,7 @@ pub fn Home() -> Element {
 
     if ctx().api_client.is_none() {
         navigator.push(Route::Login);
+        return rsx! {};
     }
 
     let client = ctx().api_client.unwrap();
@@ -64,9 +212,44 @@ pub fn Home() -> Element {
     let address = use_memo(move || public().dump_base36());
 
     let mut error = use_signal(|| "".to_string());
-    let mut balance_snap = use_signal(|| 0f64);
+    let sync = consume_context::<Signal<SyncState>>();
+    let balance_snap =
+        use_memo(move || to_snap(sync().balances.get(&public()).copied().unwrap_or(0)));
     let mut tx_history: Signal<Vec<HistoryTX>> = use_signal(|| vec![]);
     let mut need_refresh = use_signal(|| false);
+    let mut pending: Signal<Vec<(Public, TransactionId)>> = use_signal(Vec::new);
+
+    // Track mempool transactions of the vault until they are confirmed or expire
+    use_future(move || async move {
+        let mut events = sync.peek().subscribe();
+        loop {
+            match events.recv().await {
+                Ok(SyncEvent::Transaction {
+                    wallet,
+                    transaction_id,
+                    confirmed: false,
+                    ..
+                }) => {
+                    if !pending.peek().contains(&(wallet, transaction_id)) {
+                        pending.write().push((wallet, transaction_id));
+                    }
+                }
+                Ok(SyncEvent::Transaction { transaction_id, .. })
+                | Ok(SyncEvent::TransactionExpired { transaction_id }) => {
+                    pending.write().retain(|(_, id)| *id != transaction_id);
+                }
+                Ok(SyncEvent::NodeSynced) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    let pending_count = use_memo(move || {
+        pending
+            .read()
+            .iter()
+            .filter(|(wallet, _)| *wallet == public())
+            .count()
+    });
 
     // ---------------- SEND SIGNALS ----------------
     let mut recipients: Signal<Vec<(String, String)>> =
@@ -75,7 +258,15 @@ pub fn Home() -> Element {
     let mut is_sending = use_signal(|| false);
     let mut ignore_inputs = use_signal(|| Vec::new());
 
+    let mut transfer_mode = use_signal(|| false);
+    let mut transfer_from = use_signal(|| ctx().selected_wallet);
+    let mut transfer_to = use_signal(|| "".to_string());
+    let mut transfer_amount = use_signal(|| "".to_string());
+
     let total_amount = use_memo(move || {
+        if transfer_mode() {
+            return transfer_amount().parse::<f64>().unwrap_or(0.0);
+        }
         recipients()
             .iter()
             .filter_map(|(_, amt)| amt.parse::<f64>().ok())
@@ -87,74 +278,32 @@ pub fn Home() -> Element {
 
     let client_clone = client.clone();
 
-    use_coroutine(move |_: UnboundedReceiver<()>| {
+    // Reload history whenever the sync service saw the chain change
+    use_effect(move || {
+        let _ = sync.read().revision;
         let client = client_clone.clone();
-        async move {
-            loop {
-                let private = private();
-                let public = private.to_public();
-
-                if let Err(e) = async {
-                    let balance = to_snap(client.get_balance(public).await?);
-                    let tx_ids = client.get_transactions_of_address(public, Some(2)).await?;
-
-                    let mut history = vec![];
-
-                    for tx_id in tx_ids.iter().take(10) {
-                        let tx = client.get_transaction(tx_id).await?.expect("TX NOT FOUND");
-
-                        let mut my_out = 0;
-                        let mut my_in = 0;
-
-                        for input in &tx.inputs {
-                            if input.output_owner == public {
-                                let fund_tx = client
-                                    .get_transaction(&input.transaction_id)
-                                    .await?
-                                    .expect("TX NOT FOUND");
-
-                                my_out += fund_tx.outputs[input.output_index].amount;
-                            }
-                        }
-
-                        for output in &tx.outputs {
-                            if output.receiver == public {
-                                my_in += output.amount;
-                            }
-                        }
-
-                        history.push(HistoryTX {
-                            senders: tx.inputs.iter().map(|i| i.output_owner).collect(),
-                            receivers: tx.outputs.iter().map(|o| o.receiver).collect(),
-                            is_send: my_in < my_out,
-                            amount_snap: to_snap((my_out as i64 - my_in as i64).abs() as u64),
-                            tx: *tx_id,
-                            when: format_timestamp_secs(tx.timestamp),
-                        });
-                    }
-
-                    balance_snap.set(balance);
+        let public = public();
+        spawn(async move {
+            let syncing = sync.peek().syncing;
+            match fetch_history(&client, public, Some(10), &ctx.peek().wallet_names()).await {
+                Ok((history, errors)) => {
                     tx_history.set(history);
-                    error.set("".into());
-
-                    Ok::<(), BlockchainDataProviderError>(())
-                }
-                .await
-                {
-                    error.set(e.to_string());
-                }
-
-                for _ in 0..10 {
-                    sleep(Duration::from_secs(1)).await;
-                    if need_refresh() {
-                        need_refresh.set(false);
-                        break;
+                    match errors.into_iter().next() {
+                        Some(e) => error.set(format!(
+                            "Some transactions could not be loaded: {}",
+                            e.or_syncing(syncing)
+                        )),
+                        None => error.set("".into()),
                     }
                 }
+                Err(e) => error.set(e.or_syncing(syncing).to_string()),
             }
-        }
+            need_refresh.set(false);
+        });
     });
     let client = client.clone();
+    let history_client = client.clone();
+    let mut exporting = use_signal(|| false);
 
     let mut annotating_tx = use_signal(|| None);
 
@@ -186,7 +335,10 @@ pub fn Home() -> Element {
                     img {
                         src: REFRESH,
                         class: "rounded-none! cursor-pointer invert".to_string() + if need_refresh() { " spin-fast" } else { "" },
-                        onclick: move |_| need_refresh.set(true)
+                        onclick: move |_| {
+                            need_refresh.set(true);
+                            request_sync();
+                        }
                     }
                 }
             }
@@ -201,23 +353,52 @@ pub fn Home() -> Element {
                         class: "bg-neutral-900 rounded-xl p-6 shadow",
                         p { class: "text-sm text-neutral-400", "Balance" }
                         h2 { class: "text-3xl font-bold mt-2 font-mono", "{balance_snap} SNAP" }
+                        if pending_count() > 0 {
+                            p { class: "text-sm text-neutral-400 mt-2", "{pending_count} pending transactions" }
+                        }
                     }
 
                     // ---------------- SEND PANEL ----------------
                     div {
                         class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
-                        h3 { class: "font-semibold text-lg", "Send" }
+                        div {
+                            class: "flex items-center justify-between gap-2",
+                            h3 { class: "font-semibold text-lg", if transfer_mode() { "Transfer" } else { "Send" } }
+                            button {
+                                class: "text-sm p-2! bg-transparent! border! border-[var(--border)]!",
+                                onclick: move |_| {
+                                    transfer_mode.set(!transfer_mode());
+                                    tx_status.set("".to_string());
+                                },
+                                if transfer_mode() { "Send to an address" } else { "Transfer between my wallets" }
+                            }
+                        }
 
-                        for (idx, (addr, amt)) in recipients.read().iter().enumerate() {
-                            div { class: "flex gap-2",
-                                div {
-                                    class: "flex flex-col gap-3 w-full",
-                                    input {
-                                        class: "bg-neutral-800 p-2 rounded flex-1 w-full",
-                                        placeholder: "Recipient address",
-                                        value: "{addr}",
-                                        oninput: move |e| {
-                                            recipients.with_mut(|r| r[idx].0 = e.value());
+                        if transfer_mode() {
+                            {
+                                let mut wallet_names: Vec<String> = ctx().wallets.keys().cloned().collect();
+                                wallet_names.sort();
+                                let destination_names = wallet_names.clone();
+
+                                rsx! {
+                                    label { class: "text-sm text-neutral-400", "From" }
+                                    select {
+                                        class: "bg-neutral-800 p-2 rounded w-full",
+                                        value: "{transfer_from}",
+                                        onchange: move |e| transfer_from.set(e.value()),
+                                        for name in wallet_names {
+                                            option { value: "{name}", "{name}" }
+                                        }
+                                    }
+
+                                    label { class: "text-sm text-neutral-400", "To" }
+                                    select {
+                                        class: "bg-neutral-800 p-2 rounded w-full",
+                                        value: "{transfer_to}",
+                                        onchange: move |e| transfer_to.set(e.value()),
+                                        option { value: "", disabled: true, "Destination wallet" }
+                                        for name in destination_names.into_iter().filter(|name| *name != transfer_from()) {
+                                            option { value: "{name}", "{name}" }
                                         }
                                     }
 
@@ -225,31 +406,55 @@ pub fn Home() -> Element {
                                         class: "bg-neutral-800 p-2 rounded w-full font-mono font-bold",
                                         type: "number",
                                         placeholder: "Amount",
-                                        value: "{amt}",
-                                        oninput: move |e| {
-                                            recipients.with_mut(|r| r[idx].1 = e.value());
-                                        }
+                                        value: "{transfer_amount}",
+                                        oninput: move |e| transfer_amount.set(e.value())
                                     }
                                 }
+                            }
+                        } else {
+                            for (idx, (addr, amt)) in recipients.read().iter().enumerate() {
+                                div { class: "flex gap-2",
+                                    div {
+                                        class: "flex flex-col gap-3 w-full",
+                                        input {
+                                            class: "bg-neutral-800 p-2 rounded flex-1 w-full",
+                                            placeholder: "Recipient address",
+                                            value: "{addr}",
+                                            oninput: move |e| {
+                                                recipients.with_mut(|r| r[idx].0 = e.value());
+                                            }
+                                        }
 
-                                if recipients.read().len() > 1 {
-                                    button {
-                                        class: "px-2 text-red-400 bg-neutral-900! border! border-[var(--border)]!",
-                                        onclick: move |_| {
-                                            recipients.with_mut(|r| { r.remove(idx); });
-                                        },
-                                        "✕"
+                                        input {
+                                            class: "bg-neutral-800 p-2 rounded w-full font-mono font-bold",
+                                            type: "number",
+                                            placeholder: "Amount",
+                                            value: "{amt}",
+                                            oninput: move |e| {
+                                                recipients.with_mut(|r| r[idx].1 = e.value());
+                                            }
+                                        }
+                                    }
+
+                                    if recipients.read().len() > 1 {
+                                        button {
+                                            class: "px-2 text-red-400 bg-neutral-900! border! border-[var(--border)]!",
+                                            onclick: move |_| {
+                                                recipients.with_mut(|r| { r.remove(idx); });
+                                            },
+                                            "✕"
+                                        }
                                     }
                                 }
                             }
-                        }
 
-                        button {
-                            class: "text-sm text-indigo-400 self-start",
-                            onclick: move |_| {
-                                recipients.with_mut(|r| r.push((String::new(), String::new())));
-                            },
-                            "+ Add recipient"
+                            button {
+                                class: "text-sm text-indigo-400 self-start",
+                                onclick: move |_| {
+                                    recipients.with_mut(|r| r.push((String::new(), String::new())));
+                                },
+                                "+ Add recipient"
+                            }
                         }
 
                         div {
@@ -262,6 +467,16 @@ pub fn Home() -> Element {
                             disabled: is_sending(),
                             onclick: move |_| {
                                 let client = client.clone();
+                                let sender = if transfer_mode() {
+                                    ctx().wallets.get(&transfer_from()).copied()
+                                } else {
+                                    Some(private())
+                                };
+                                let Some(sender) = sender else {
+                                    tx_status.set("Choose a source wallet".to_string());
+                                    return;
+                                };
+
                                 spawn(async move {
 
                                     tx_status.set("".to_string());
@@ -274,12 +489,24 @@ pub fn Home() -> Element {
                                         }
 
                                         let mut receivers = vec![];
-                                        for (r, a) in recipients() {
-                                            receivers.push((Public::new_from_base36(&r).ok_or(anyhow!("Invalid receiver address"))?, to_nano(a.parse().map_err(|_| anyhow!("Invalid amount"))?)));
+                                        if transfer_mode() {
+                                            let destination = ctx()
+                                                .wallets
+                                                .get(&transfer_to())
+                                                .ok_or(anyhow!("Choose a destination wallet"))?
+                                                .to_public();
+                                            if destination == sender.to_public() {
+                                                return Err(anyhow!("Source and destination wallets must differ"));
+                                            }
+                                            receivers.push((destination, to_nano(transfer_amount().parse().map_err(|_| anyhow!("Invalid amount"))?)));
+                                        } else {
+                                            for (r, a) in recipients() {
+                                                receivers.push((Public::new_from_base36(&r).ok_or(anyhow!("Invalid receiver address"))?, to_nano(a.parse().map_err(|_| anyhow!("Invalid amount"))?)));
+                                            }
                                         }
                                         let mut ignore_inputs = ignore_inputs.write();
                                         tx_status.set("Building transaction...".to_string());
-                                        let mut tx = build_transaction(&*client_clone, private(), receivers, &*ignore_inputs).await?;
+                                        let mut tx = build_transaction(&*client_clone, sender, receivers, &ignore_inputs).await?;
                                         let used_inputs = tx.inputs.clone();
                                         tx_status.set("Computing transaction PoW...".to_string());
                                         tx.compute_pow(&client_clone.get_live_transaction_difficulty().await?, Some(0.2f64))?;
@@ -292,15 +519,15 @@ pub fn Home() -> Element {
                                         if let Some(UtilError::TooMuchIO) = e.downcast_ref::<UtilError>() {
                                             if let Err(e) = async move {
                                                 let available = client
-                                                    .get_available_transaction_outputs(private().to_public())
+                                                    .get_available_transaction_outputs(sender.to_public())
                                                     .await?;
                                                 let mut part_count = 0;
                                                 for part in available.chunks(MAX_TRANSACTION_IO - 1) {
                                                     let amount = part.iter().fold(0, |acc, part| part.1.amount + acc);
                                                     let mut tx = build_transaction(
                                                         &*client,
-                                                        private(),
-                                                        vec![(private().to_public(), amount)],
+                                                        sender,
+                                                        vec![(sender.to_public(), amount)],
                                                         &ignore_inputs.write(),
                                                     )
                                                     .await?;
@@ -331,7 +558,9 @@ pub fn Home() -> Element {
                                         }
                                     } else {
                                         recipients.set(vec![(String::new(), String::new())]);
+                                        transfer_amount.set("".to_string());
                                         tx_status.set("Transaction submitted".to_string());
+                                        request_sync();
                                     }
 
                                     is_sending.set(false);
@@ -349,7 +578,45 @@ pub fn Home() -> Element {
 
                 div {
                     class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col overflow-hidden",
-                    h3 { class: "font-semibold text-lg mb-4", "Transaction History" }
+                    div {
+                        class: "flex items-center justify-between mb-4",
+                        h3 { class: "font-semibold text-lg", "Transaction History" }
+                        button {
+                            class: "text-sm p-2! bg-transparent! border! border-[var(--border)]!",
+                            disabled: tx_history.read().is_empty() || exporting(),
+                            onclick: move |_| {
+                                let Some(path) = FileDialog::new()
+                                    .set_title("Export History")
+                                    .set_file_name(format!("history-{}.csv", &address()[0..8]))
+                                    .add_filter("CSV", &["csv"])
+                                    .save_file()
+                                else {
+                                    return;
+                                };
+                                let client = history_client.clone();
+                                spawn(async move {
+                                    exporting.set(true);
+                                    // Only the latest transactions are loaded, the export covers all of them
+                                    match fetch_history(&client, public(), None, &ctx.peek().wallet_names()).await {
+                                        Ok((history, errors)) => match errors.into_iter().next() {
+                                            Some(e) => error.set(format!(
+                                                "Some transactions could not be loaded, the history was not exported: {e}"
+                                            )),
+                                            None => {
+                                                let csv = history_csv(&history, &ctx.peek().annotations);
+                                                if let Err(e) = fs::write(&path, csv) {
+                                                    error.set(format!("Failed to export history: {e}"));
+                                                }
+                                            }
+                                        },
+                                        Err(e) => error.set(format!("Failed to export history: {e}")),
+                                    }
+                                    exporting.set(false);
+                                });
+                            },
+                            if exporting() { "Exporting..." } else { "Export CSV" }
+                        }
+                    }
 
                     div {
                         class: "flex flex-col gap-2 overflow-auto pr-2",
@@ -366,20 +633,34 @@ pub fn Home() -> Element {
                                 let sender_more = tx.senders.len().saturating_sub(1);
                                 let receiver_more = tx.receivers.len().saturating_sub(1);
 
-                                let amount_class = if tx.is_send { "text-red-400" } else { "text-green-400" };
+                                let amount_class = if tx.internal_with.is_some() { "text-[var(--accent)]" } else if tx.is_send { "text-red-400" } else { "text-green-400" };
                                 let sign = if tx.is_send { "-" } else { "+" };
                                 let amount_text = format!("{sign}{:.4}", tx.amount_snap);
+                                let internal_text = tx.internal_with.as_ref().map(|wallet| {
+                                    if tx.is_send { format!("Internal transfer to {wallet}") } else { format!("Internal transfer from {wallet}") }
+                                });
+
+                                let annotation_title = ctx().annotations.get(&tx.tx).map(|a| a.title.clone()).filter(|title| !title.is_empty());
 
                                 let tx_id = tx.tx.clone();
                                 let tx_id_clone = tx.tx.clone();
-                                let sender_main_clone = sender_main.clone();
-                                let receiver_main_clone = receiver_main.clone();
+                                let sender_address = tx.senders.first().copied();
+                                let receiver_address = tx.receivers.first().copied();
 
                                 rsx! {
                                     div {
                                         class: "bg-neutral-800 p-4 rounded grid grid-cols-2 gap-x-4 gap-y-3 text-sm",
 
-                                        span { class: "font-semibold text-xl font-bold {amount_class}", "{amount_text} SNAP" }
+                                        div {
+                                            class: "flex flex-col",
+                                            span { class: "font-semibold text-xl font-bold {amount_class}", "{amount_text} SNAP" }
+                                            if let Some(internal_text) = internal_text {
+                                                span { class: "text-neutral-500 text-xs", "{internal_text}" }
+                                            }
+                                            if let Some(annotation_title) = annotation_title {
+                                                span { class: "text-neutral-300 truncate", title: "Annotation", "{annotation_title}" }
+                                            }
+                                        }
                                         div {
                                             class: "flex items-center gap-5",
                                             span { class: "text-neutral-500 text-xs whitespace-nowrap text-right", "{tx.when.clone()}" }
@@ -390,8 +671,8 @@ pub fn Home() -> Element {
                                                     "Transaction ID"
                                                 }
                                                 CopyBox { onclick: move |_| {
-                                                    let _ = webbrowser::open(&("https://explorer.snap-coin.net/tx/".to_string() + &tx_id_clone.dump_base36()));
-                                                }, class: "w-full min-w-0", text: tx_id_clone.dump_base36(), title: "Transaction ID" }
+                                                    navigator.push(Route::TransactionDetail { id: tx_id_clone.dump_base36() });
+                                                }, class: "w-full min-w-0 cursor-pointer", text: tx_id_clone.dump_base36(), title: "Transaction ID" }
                                                 img {
                                                     src: FILE_CHECK,
                                                     class: "rounded-none! cursor-pointer invert".to_string() + if need_refresh() { " spin-fast" } else { "" },
@@ -404,8 +685,10 @@ pub fn Home() -> Element {
                                         div {
                                             class: "flex items-center gap-2 min-w-0",
                                             CopyBox { onclick: move |_| {
-                                                let _ = webbrowser::open(&("https://explorer.snap-coin.net/wallet/".to_string() + &sender_main));
-                                            }, class: "w-full min-w-0", text: sender_main_clone, title: "Sender" }
+                                                if let Some(sender) = sender_address {
+                                                    navigator.push(Route::AddressView { address: sender.dump_base36() });
+                                                }
+                                            }, class: "w-full min-w-0 cursor-pointer", text: sender_main, title: "Sender" }
                                             if sender_more > 0 { span { class: "text-neutral-500 shrink-0", "+{sender_more}" } }
                                         }
 
@@ -413,8 +696,10 @@ pub fn Home() -> Element {
                                         div {
                                             class: "flex items-center gap-2 min-w-0",
                                             CopyBox { onclick: move |_| {
-                                                let _ = webbrowser::open(&("https://explorer.snap-coin.net/wallet/".to_string() + &receiver_main));
-                                            }, class: "w-full min-w-0", text: receiver_main_clone, title: "Receiver" }
+                                                if let Some(receiver) = receiver_address {
+                                                    navigator.push(Route::AddressView { address: receiver.dump_base36() });
+                                                }
+                                            }, class: "w-full min-w-0 cursor-pointer", text: receiver_main, title: "Receiver" }
                                             if receiver_more > 0 { span { class: "text-neutral-500 shrink-0", "+{receiver_more}" } }
                                         }
                                     }
@@ -425,7 +710,20 @@ pub fn Home() -> Element {
                 }
             }
 
-            p { class: "p-10 text-red-400! font-bold", "{error}" }
+            if sync().error.is_some() || !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{sync().error.unwrap_or(error())}" }
+                    button {
+                        disabled: need_refresh(),
+                        onclick: move |_| {
+                            need_refresh.set(true);
+                            request_sync();
+                        },
+                        "Retry"
+                    }
+                }
+            }
         }
     }
 }
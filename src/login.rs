@@ -8,8 +8,8 @@ use anyhow::anyhow;
 use dioxus::prelude::*;
 
 use crate::{
-    authorize::ask_for_auth, encryption::decrypt_wallets, save_wallets, wallet_path, GlobalContext,
-    Route,
+    annotations::load_annotations, authorize::ask_for_auth, encryption::decrypt_wallets,
+    save_wallets, settings::load_settings, wallet_path, GlobalContext, Route,
 };
 
 #[component]
@@ -43,6 +43,14 @@ pub fn Login() -> Element {
                     let mut buf = Vec::new();
                     file.read_to_end(&mut buf)?;
                     if let Some(wallets) = decrypt_wallets(&buf, &pin) {
+                        match load_settings(&pin) {
+                            Ok(settings) => ctx.write().settings = settings,
+                            Err(e) => ctx.write().settings_error = Some(e.to_string()),
+                        }
+                        match load_annotations(&pin) {
+                            Ok(annotations) => ctx.write().annotations = annotations,
+                            Err(e) => ctx.write().annotations_error = Some(e.to_string()),
+                        }
                         ctx.write().wallets = wallets.clone();
                         if let Some(wallet) = wallets.keys().next() {
                             ctx.write().selected_wallet = wallet.clone();
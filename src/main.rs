@@ -1,16 +1,39 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use dioxus::prelude::*;
 // use home::Home;
+use annotations::Annotations;
+use api_access::ApiAccessSettings;
 use anyhow::Error;
 use connection::Connection;
+use explorer::{AddressView, BlockDetail, Explorer, TransactionDetail};
 use futures_channel::oneshot;
 use home::Home;
 use login::Login;
+use messages::{SignMessage, VerifyMessage};
 use navigation::NavigationBar;
+use node_supervisor::{NodeLifecycle, NodeSupervisor, SyncProgress};
+use notifications::use_notifications;
+use offline_signing::OfflineSigning;
+use peers::Peers;
+use portfolio::Portfolio;
+use receipt_verifier::ReceiptVerifier;
+use settings::Settings;
+use statement::{StatementGenerator, StatementVerifier};
+use sync::use_sync_service;
+use transaction_tool::TransactionTool;
 use snap_coin::{
     api::client::Client,
-    crypto::{keys::Private, Hash},
+    crypto::{
+        keys::{Private, Public},
+        Hash,
+    },
     full_node::{node_state::SharedNodeState, SharedBlockchain},
 };
 use tokio::sync::Mutex;
@@ -19,18 +42,39 @@ use wallet_manager::WalletManager;
 use crate::encryption::encrypt_wallets;
 
 // Screens
+mod api_access;
 mod connection;
+mod connection_settings;
 mod encryption;
+mod explorer;
 mod home;
 mod login;
+mod messages;
 mod navigation;
+mod notifications;
+mod offline_signing;
+mod portfolio;
+mod receipt_verifier;
+mod settings;
+mod statement;
+mod sync;
+mod transaction_tool;
 mod wallet_manager;
 
 // Components
 mod authorize;
 mod copy_box;
+mod node_api;
 mod node_log;
+mod node_storage;
+mod node_supervisor;
+mod peers;
 mod annotate;
+mod annotations;
+mod chain;
+mod receipt;
+mod receipt_document;
+mod transaction_file;
 
 pub const LOGO: Asset = asset!("assets/logo.svg");
 
@@ -50,6 +94,19 @@ pub fn save_wallets(wallets: &HashMap<String, Private>, pin: &str) -> Result<(),
     Ok(())
 }
 
+/// Write a file through a temporary one next to it, so it is never left half written
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp = PathBuf::from(temp_name);
+
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(temp, path)?;
+    Ok(())
+}
+
 #[derive(Clone, Debug, PartialEq, Routable)]
 pub enum Route {
     #[layout(NavigationBar)]
@@ -61,40 +118,120 @@ pub enum Route {
     Home,
     #[route("/wallet-manager")]
     WalletManager,
+    #[route("/portfolio")]
+    Portfolio,
+    #[route("/offline-signing")]
+    OfflineSigning,
+    #[route("/transaction-inspector")]
+    TransactionTool,
+    #[route("/sign-message")]
+    SignMessage,
+    #[route("/verify-message")]
+    VerifyMessage,
+    #[route("/verify-receipt")]
+    ReceiptVerifier,
+    #[route("/statement")]
+    StatementGenerator,
+    #[route("/verify-statement")]
+    StatementVerifier,
+    #[route("/peers")]
+    Peers,
+    #[route("/api-access")]
+    ApiAccessSettings,
+    #[route("/explorer")]
+    Explorer,
+    #[route("/block/:hash")]
+    BlockDetail { hash: String },
+    #[route("/transaction/:id")]
+    TransactionDetail { id: String },
+    #[route("/address/:address")]
+    AddressView { address: String },
 }
 
 #[derive(Clone)]
 pub struct NodeHandle {
-    _node_state: SharedNodeState,
-    _blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+    blockchain: SharedBlockchain,
     log_file: PathBuf,
+    /// Runtime of the node, peers connected from the UI have to live on it
+    runtime: tokio::runtime::Handle,
+    supervisor: NodeSupervisor,
+}
+
+impl NodeHandle {
+    pub fn lifecycle(&self) -> NodeLifecycle {
+        self.supervisor.lifecycle()
+    }
+
+    pub fn progress(&self) -> SyncProgress {
+        self.supervisor.progress()
+    }
 }
 
 #[derive(Clone)]
 pub struct GlobalContext {
     api_client: Option<Arc<Client>>,
+    /// Token `api_client` was opened with, every other connection to the same node presents it too
+    api_token: Option<String>,
     internal_node: Option<NodeHandle>,
     wallets: HashMap<String, Private>, // Name, key
     selected_wallet: String,
     pin: String,
     show_auth: bool,
     auth_tx: Option<Arc<Mutex<Option<oneshot::Sender<bool>>>>>,
+    settings: Settings,
+    /// Why the settings file did not load, nothing is saved over it until it does
+    settings_error: Option<String>,
+    annotations: Annotations,
+    /// Why the annotations file did not load, nothing is saved over it until it does
+    annotations_error: Option<String>,
+}
+
+impl GlobalContext {
+    /// Maps the public address of every vault wallet to its name
+    pub fn wallet_names(&self) -> HashMap<Public, String> {
+        self.wallets
+            .iter()
+            .map(|(name, private)| (private.to_public(), name.clone()))
+            .collect()
+    }
 }
 
 fn main() {
     Hash::new(b"INIT"); // Get random x init
-    dioxus::launch(|| {
-        use_context_provider(|| {
+
+    // The window is gone by then, stop the node before the process exits under it
+    #[cfg(feature = "desktop")]
+    let launcher = dioxus::LaunchBuilder::desktop().with_cfg(dioxus::desktop::Config::new().with_custom_event_handler(
+        |event, _| {
+            if let dioxus::desktop::tao::event::Event::LoopDestroyed = event {
+                node_supervisor::shutdown_all();
+            }
+        },
+    ));
+    // Picks the platform from the enabled features, deprecated when there is none like `dioxus::launch`
+    #[cfg(not(feature = "desktop"))]
+    #[allow(deprecated)]
+    let launcher = dioxus::LaunchBuilder::new();
+    launcher.launch(|| {
+        let global = use_context_provider(|| {
             Signal::new(GlobalContext {
                 internal_node: None,
                 api_client: None,
+                api_token: None,
                 wallets: HashMap::new(),
                 selected_wallet: "".to_string(),
                 pin: "".to_string(),
                 show_auth: false,
                 auth_tx: None,
+                settings: Settings::default(),
+                settings_error: None,
+                annotations: Annotations::new(),
+                annotations_error: None,
             })
         });
+        let sync = use_sync_service(global);
+        use_notifications(global, sync);
 
         rsx! {
             document::Stylesheet {
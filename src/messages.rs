@@ -0,0 +1,233 @@
+use std::fs;
+
+use dioxus::prelude::*;
+use rfd::FileDialog;
+use snap_coin::crypto::{
+    keys::{Private, Public},
+    Signature,
+};
+
+use crate::{authorize::ask_for_auth, copy_box::CopyBox, GlobalContext};
+
+/// Prefixed to every signed message, so a message signature can never double as a transaction signature
+const MESSAGE_PREFIX: &[u8] = b"Snap Coin Signed Message:\n";
+
+fn message_signing_buf(message: &[u8]) -> Vec<u8> {
+    [MESSAGE_PREFIX, message].concat()
+}
+
+pub fn sign_message(mut private: Private, message: &[u8]) -> Signature {
+    Signature::new_signature(&mut private, &message_signing_buf(message))
+}
+
+pub fn verify_message(address: &Public, message: &[u8], signature: &Signature) -> bool {
+    signature
+        .validate_with_public(address, &message_signing_buf(message))
+        .unwrap_or(false)
+}
+
+/// A message typed in, or the contents of a file
+#[derive(Clone, PartialEq)]
+enum MessageSource {
+    Text(String),
+    File { name: String, contents: Vec<u8> },
+}
+
+impl MessageSource {
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            MessageSource::Text(text) => text.as_bytes().to_vec(),
+            MessageSource::File { contents, .. } => contents.clone(),
+        }
+    }
+}
+
+/// Text area for the message, which can be swapped out for a file
+#[component]
+fn MessageInput(source: Signal<MessageSource>, error: Signal<String>) -> Element {
+    rsx! {
+        match source() {
+            MessageSource::Text(text) => rsx! {
+                textarea {
+                    class: "bg-neutral-800 p-2 rounded w-full h-60",
+                    value: "{text}",
+                    oninput: move |e| source.set(MessageSource::Text(e.value())),
+                    placeholder: "Message..."
+                }
+                button {
+                    class: "bg-transparent! border! border-[var(--border)]!",
+                    onclick: move |_| {
+                        let Some(path) = FileDialog::new().set_title("Open File").pick_file() else {
+                            return;
+                        };
+                        match fs::read(&path) {
+                            Ok(contents) => source.set(MessageSource::File {
+                                name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                                contents,
+                            }),
+                            Err(e) => error.set(format!("Could not read file: {e}")),
+                        }
+                    },
+                    "Use a file instead"
+                }
+            },
+            MessageSource::File { name, contents } => rsx! {
+                div {
+                    class: "bg-neutral-800 p-4 rounded flex items-center justify-between gap-5 text-sm",
+                    span { class: "truncate", "{name}" }
+                    span { class: "text-neutral-500 text-nowrap", "{contents.len()} bytes" }
+                }
+                button {
+                    class: "bg-transparent! border! border-[var(--border)]!",
+                    onclick: move |_| source.set(MessageSource::Text(String::new())),
+                    "Type a message instead"
+                }
+            },
+        }
+    }
+}
+
+#[component]
+pub fn SignMessage() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let source = use_signal(|| MessageSource::Text(String::new()));
+    let mut signer = use_signal(|| global().selected_wallet);
+    let mut signature: Signal<Option<(Public, Signature)>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+
+    // A signature only belongs to the message and signer it was made for
+    use_effect(move || {
+        source.read();
+        signer.read();
+        signature.set(None);
+    });
+
+    let mut wallet_names: Vec<String> = global().wallets.keys().cloned().collect();
+    wallet_names.sort();
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Sign Message" }
+            p { "Prove you own an address by signing a message with its wallet." }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 max-w-300",
+
+                label { "Wallet" }
+                select {
+                    value: "{signer}",
+                    onchange: move |e| signer.set(e.value()),
+                    for name in wallet_names {
+                        option { value: "{name}", "{name}" }
+                    }
+                }
+
+                label { "Message" }
+                MessageInput { source, error: status }
+
+                button {
+                    onclick: move |_| {
+                        let Some(private) = global().wallets.get(&signer()).copied() else {
+                            status.set("Choose a wallet".to_string());
+                            return;
+                        };
+                        spawn(async move {
+                            if !ask_for_auth().await {
+                                status.set("Unauthorized".to_string());
+                                return;
+                            }
+                            signature.set(Some((private.to_public(), sign_message(private, &source().bytes()))));
+                            status.set("".to_string());
+                        });
+                    },
+                    "Sign"
+                }
+
+                if let Some((address, signature)) = signature() {
+                    div {
+                        class: "grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                        span { class: "text-neutral-500", "Address" }
+                        CopyBox { class: "w-full min-w-0", text: address.dump_base36(), title: "Signer address" }
+                        span { class: "text-neutral-500", "Signature" }
+                        CopyBox { class: "w-full min-w-0", text: signature.dump_base36(), title: "Message signature" }
+                    }
+                }
+
+                p { "{status}" }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn VerifyMessage() -> Element {
+    let source = use_signal(|| MessageSource::Text(String::new()));
+    let mut address = use_signal(|| "".to_string());
+    let mut signature = use_signal(|| "".to_string());
+    let mut result: Signal<Option<bool>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+
+    use_effect(move || {
+        source.read();
+        address.read();
+        signature.read();
+        result.set(None);
+    });
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Verify Message" }
+            p { "Check that a message was signed by the owner of an address." }
+
+            div {
+                class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 max-w-300",
+
+                label { "Message" }
+                MessageInput { source, error: status }
+
+                label { "Address" }
+                input {
+                    class: "font-mono",
+                    value: "{address}",
+                    oninput: move |e| address.set(e.value().trim().to_string()),
+                    placeholder: "Base36 address"
+                }
+
+                label { "Signature" }
+                input {
+                    class: "font-mono",
+                    value: "{signature}",
+                    oninput: move |e| signature.set(e.value().trim().to_string()),
+                    placeholder: "Base36 signature"
+                }
+
+                button {
+                    onclick: move |_| {
+                        let Some(public) = Public::new_from_base36(&address()) else {
+                            status.set("Invalid address".to_string());
+                            return;
+                        };
+                        let Some(parsed) = Signature::new_from_base36(&signature()) else {
+                            status.set("Invalid signature".to_string());
+                            return;
+                        };
+                        status.set("".to_string());
+                        result.set(Some(verify_message(&public, &source().bytes(), &parsed)));
+                    },
+                    "Verify"
+                }
+
+                match result() {
+                    Some(true) => rsx! { p { class: "text-green-400 font-bold", "Valid, the message was signed by this address" } },
+                    Some(false) => rsx! { p { class: "text-red-400 font-bold", "Invalid, the signature does not match this message and address" } },
+                    None => rsx! {},
+                }
+
+                p { "{status}" }
+            }
+        }
+    }
+}
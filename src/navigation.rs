@@ -1,6 +1,8 @@
 use dioxus::prelude::*;
 
-use crate::{authorize::Authorize, node_log::NodeLog, GlobalContext, Route, LOGO};
+use crate::{
+    annotations::load_annotations, authorize::Authorize, connection::{NodeControls, SyncIndicator}, node_log::NodeLog, settings::load_settings, GlobalContext, Route, LOGO,
+};
 
 const WALLET_ICON: Asset = asset!("../assets/wallet.svg");
 
@@ -11,6 +13,7 @@ pub fn NavigationBar() -> Element {
 
     let mut wallet_drop_open = use_signal(|| false);
     let mut node_log_open = use_signal(|| false);
+    let mut tools_drop_open = use_signal(|| false);
 
     rsx! {
         div {
@@ -29,9 +32,38 @@ pub fn NavigationBar() -> Element {
             div {
                 class: "relative flex flex-row items-center gap-5",
 
+                if global_context().api_client.is_some() {
+                    button {
+                        onclick: move |_| {
+                            navigator.replace(Route::Portfolio);
+                        },
+                        "Portfolio"
+                    }
+                    button {
+                        onclick: move |_| {
+                            navigator.replace(Route::Explorer);
+                        },
+                        "Explorer"
+                    }
+                }
+
                 {
                     if global_context().internal_node.is_some() {
                         rsx! {
+                            SyncIndicator {}
+                            NodeControls {}
+                            button {
+                                onclick: move |_| {
+                                    navigator.replace(Route::Peers);
+                                },
+                                "Peers"
+                            }
+                            button {
+                                onclick: move |_| {
+                                    navigator.replace(Route::ApiAccessSettings);
+                                },
+                                "API Access"
+                            }
                             button {
                                 onclick: move |_| {
                                     node_log_open.set(!node_log_open());
@@ -42,6 +74,41 @@ pub fn NavigationBar() -> Element {
                     } else { rsx! {} }
                 }
 
+                if !global_context().pin.is_empty() {
+                    div {
+                        class: "relative",
+                        button {
+                            onclick: move |_| {
+                                tools_drop_open.set(!tools_drop_open());
+                            },
+                            "Tools"
+                        }
+
+                        div {
+                            class: format!("absolute top-full right-0 mt-1 z-50 w-60 border border-gray-600 bg-[var(--bg)] p-2 flex flex-col rounded-lg shadow-lg transition-all {}", if tools_drop_open() { "" } else { "opacity-0 -translate-y-500" }),
+
+                            for (label, route) in [
+                                ("Offline signing", Route::OfflineSigning),
+                                ("Transaction inspector", Route::TransactionTool),
+                                ("Sign message", Route::SignMessage),
+                                ("Verify message", Route::VerifyMessage),
+                                ("Verify receipt", Route::ReceiptVerifier),
+                                ("Account statement", Route::StatementGenerator),
+                                ("Verify statement", Route::StatementVerifier),
+                            ] {
+                                p {
+                                    class: "hover:bg-gray-900 p-1 px-2 m-1 rounded-md cursor-pointer truncate",
+                                    onclick: move |_| {
+                                        navigator.replace(route.clone());
+                                        tools_drop_open.set(false);
+                                    },
+                                    "{label}"
+                                }
+                            }
+                        }
+                    }
+                }
+
                 // Wallet button
                 div {
                     class: format!("border border-gray-600 p-2 gap-2 flex flex-row items-center rounded-lg hover:cursor-pointer w-40 {} justify-between transition-all", if wallet_drop_open() { "w-100" } else { "" }),
@@ -103,7 +170,61 @@ pub fn NavigationBar() -> Element {
             }
 
         }
+        LoadErrors {}
         Outlet::<Route> {}
         Authorize {}
     }
 }
+
+/// Files that failed to load at login, kept untouched until they load
+#[component]
+fn LoadErrors() -> Element {
+    let mut global = consume_context::<Signal<GlobalContext>>();
+    let errors: Vec<String> = [
+        global().settings_error.map(|e| format!("Your settings failed to load: {e}.")),
+        global().annotations_error.map(|e| format!("Your annotations failed to load: {e}.")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if errors.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "flex items-center justify-between gap-5 px-5 py-3 bg-red-950 text-red-300 text-sm",
+            div {
+                for error in errors {
+                    p { "{error}" }
+                }
+                p { "They are left as they are on disk, and changes to them are not saved until they load." }
+            }
+            button {
+                class: "bg-transparent! border! border-red-400! text-red-300! text-xs",
+                onclick: move |_| {
+                    let pin = global.peek().pin.clone();
+                    if global.peek().settings_error.is_some() {
+                        match load_settings(&pin) {
+                            Ok(settings) => global.with_mut(|g| {
+                                g.settings = settings;
+                                g.settings_error = None;
+                            }),
+                            Err(e) => global.write().settings_error = Some(e.to_string()),
+                        }
+                    }
+                    if global.peek().annotations_error.is_some() {
+                        match load_annotations(&pin) {
+                            Ok(annotations) => global.with_mut(|g| {
+                                g.annotations = annotations;
+                                g.annotations_error = None;
+                            }),
+                            Err(e) => global.write().annotations_error = Some(e.to_string()),
+                        }
+                    }
+                },
+                "Retry"
+            }
+        }
+    }
+}
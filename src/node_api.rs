@@ -0,0 +1,551 @@
+use std::{
+    io::ErrorKind,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snap_coin::{
+    api::{
+        api_server::PAGE_SIZE,
+        client::Client,
+        requests::{Request, Response},
+    },
+    blockchain_data_provider::BlockchainDataProvider,
+    core::{
+        blockchain::BlockchainError, difficulty::calculate_live_transaction_difficulty,
+        transaction::TransactionError, utils::slice_vec,
+    },
+    crypto::Hash,
+    economics::get_block_reward,
+    full_node::{accept_block, accept_transaction, node_state::SharedNodeState, SharedBlockchain},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::sleep,
+};
+
+use crate::connection_settings::{ApiAccess, ApiBind, ApiPermission};
+
+/// Ports below this need elevated rights on most systems and are never bound
+pub const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+/// Pause after a failed accept, it keeps failing while the process is out of file descriptors
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(500);
+const READ_ONLY: &str = "This API token may only read";
+/// Largest request accepted, well above the largest block
+const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+/// Sent by a client before its first request to present an API token, framed like a request
+#[derive(Serialize, Deserialize)]
+pub struct ApiAuth {
+    pub token: String,
+}
+
+/// Answer to `ApiAuth`, the connection is closed after a refusal
+#[derive(Serialize, Deserialize)]
+pub struct ApiAuthReply {
+    pub permission: Option<ApiPermission>,
+    pub error: Option<String>,
+}
+
+/// A new random API token, shown to the user once
+pub fn generate_api_token() -> String {
+    Hash::new_from_buf(rand::random()).dump_base36()
+}
+
+/// What `ApiToken::hash` holds for a token
+pub fn hash_api_token(token: &str) -> String {
+    Hash::new_from_buf(Sha256::digest(token.trim().as_bytes()).into()).dump_base36()
+}
+
+/// What a client may do, this machine may do anything and other machines without a token may only read
+pub fn grant(
+    access: &ApiAccess,
+    ip: IpAddr,
+    token_hash: Option<&str>,
+) -> Result<ApiPermission, String> {
+    if ip.is_loopback() {
+        return Ok(ApiPermission::Submit);
+    }
+    if !access.allowed_ips.is_empty() && !access.allowed_ips.contains(&ip) {
+        return Err(format!("{ip} is not allowed to use this API"));
+    }
+    match token_hash {
+        Some(hash) => access
+            .tokens
+            .iter()
+            .find(|token| token.hash == hash)
+            .map(|token| token.permission)
+            .ok_or("Unknown or revoked API token".to_string()),
+        None if access.require_token => Err("This API requires a token".to_string()),
+        // Never more than a token gives, or leaving out a read only token would lift its limit
+        None => Ok(ApiPermission::ReadOnly),
+    }
+}
+
+/// The answer to a submission a read only client may not make, none when the request may go ahead
+fn refuse_read_only(permission: ApiPermission, request: &Request) -> Option<Response> {
+    if permission != ApiPermission::ReadOnly {
+        return None;
+    }
+    // The response has no room for a permission error, the submission is reported as rejected
+    match request {
+        Request::NewBlock { .. } => Some(Response::NewBlock {
+            status: Err(BlockchainError::Io(READ_ONLY.to_string())),
+        }),
+        Request::NewTransaction { .. } => Some(Response::NewTransaction {
+            status: Err(BlockchainError::InvalidTransaction(READ_ONLY.to_string())),
+        }),
+        _ => None,
+    }
+}
+
+/// Read one length prefixed frame, none when the other side closed the connection between frames
+async fn read_frame(stream: &mut TcpStream) -> Result<Option<Vec<u8>>, anyhow::Error> {
+    let mut size = [0u8; 4];
+    match stream.read_exact(&mut size).await {
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    };
+    let size = u32::from_be_bytes(size) as usize;
+    if size > MAX_FRAME {
+        return Err(anyhow!("Request of {size} bytes is too large"));
+    }
+    let mut frame = vec![0u8; size];
+    stream.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+async fn write_frame(
+    stream: &mut TcpStream,
+    message: &impl Serialize,
+) -> Result<(), anyhow::Error> {
+    let message = serde_json::to_vec(message)?;
+    let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+    frame.extend(message);
+    stream.write_all(&frame).await?;
+    Ok(())
+}
+
+async fn exchange<T: DeserializeOwned>(
+    stream: &mut TcpStream,
+    message: &impl Serialize,
+) -> Result<T, anyhow::Error> {
+    write_frame(stream, message).await?;
+    let reply = read_frame(stream)
+        .await?
+        .ok_or(anyhow!("The connection was closed"))?;
+    Ok(serde_json::from_slice(&reply)?)
+}
+
+/// Present an API token on a fresh connection, before any request is made on it
+pub async fn authenticate(client: &Client, token: &str) -> Result<ApiPermission, anyhow::Error> {
+    let mut stream = client.stream.lock().await;
+    let reply: ApiAuthReply = exchange(
+        &mut stream,
+        &ApiAuth {
+            token: token.trim().to_string(),
+        },
+    )
+    .await
+    .map_err(|e| anyhow!("The node did not accept a token: {e}"))?;
+    match (reply.permission, reply.error) {
+        (Some(permission), _) => Ok(permission),
+        (None, error) => Err(anyhow!(error.unwrap_or("API token refused".to_string()))),
+    }
+}
+
+/// Connect to a node API, presenting the token when there is one
+pub async fn connect_api(
+    address: SocketAddr,
+    token: Option<&str>,
+) -> Result<Client, anyhow::Error> {
+    let client = Client::connect(address).await?;
+    if let Some(token) = token {
+        authenticate(&client, token).await?;
+    }
+    Ok(client)
+}
+
+/// Bind the API listener, on `preferred` when it is free and otherwise on a port picked by the OS
+pub async fn bind_api(bind: ApiBind, preferred: Option<u16>) -> Result<TcpListener, anyhow::Error> {
+    if let Some(port) = preferred.filter(|port| *port >= FIRST_UNPRIVILEGED_PORT) {
+        match TcpListener::bind(SocketAddr::new(bind.ip(), port)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                info!("API port {port} is in use, letting the OS pick one");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    TcpListener::bind(SocketAddr::new(bind.ip(), 0))
+        .await
+        .map_err(|e| anyhow!("Could not bind the API: {e}"))
+}
+
+/// Serve the Snap Coin API on a bound listener, like `api_server::Server` does on its own port
+pub async fn serve_api(
+    listener: TcpListener,
+    blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+    access: Arc<Mutex<ApiAccess>>,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                tokio::spawn(connection(
+                    stream,
+                    address.ip(),
+                    blockchain.clone(),
+                    node_state.clone(),
+                    access.clone(),
+                ));
+            }
+            Err(e) => {
+                warn!("API client failed to connect: {e}");
+                sleep(ACCEPT_BACKOFF).await;
+            }
+        }
+    }
+}
+
+async fn connection(
+    mut stream: TcpStream,
+    ip: IpAddr,
+    blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+    access: Arc<Mutex<ApiAccess>>,
+) {
+    let mut token_hash: Option<String> = None;
+    let mut first = true;
+    loop {
+        // Ok(false) ends the connection quietly, when the client left or the node stops
+        let result = async {
+            let Some(frame) = read_frame(&mut stream).await? else {
+                return Ok(false);
+            };
+
+            // A token can only be presented first, anything else is a request
+            if std::mem::take(&mut first) {
+                if let Ok(auth) = serde_json::from_slice::<ApiAuth>(&frame) {
+                    let hash = hash_api_token(&auth.token);
+                    let granted = grant(&access.lock().unwrap(), ip, Some(&hash));
+                    token_hash = Some(hash);
+                    let reply = match &granted {
+                        Ok(permission) => ApiAuthReply {
+                            permission: Some(*permission),
+                            error: None,
+                        },
+                        Err(e) => ApiAuthReply {
+                            permission: None,
+                            error: Some(e.clone()),
+                        },
+                    };
+                    write_frame(&mut stream, &reply).await?;
+                    return granted.map(|_| true).map_err(|e| anyhow!("{ip}: {e}"));
+                }
+            }
+
+            // Checked on every request, so revoking a token or address takes effect right away
+            let permission = grant(&access.lock().unwrap(), ip, token_hash.as_deref())
+                .map_err(|e| anyhow!("{ip}: {e}"))?;
+            let request: Request = serde_json::from_slice(&frame)?;
+            if let Some(refusal) = refuse_read_only(permission, &request) {
+                stream.write_all(&refusal.encode()?).await?;
+                return Ok(true);
+            }
+            let response = match request {
+                // Streams events until the client leaves, nothing else is answered on this connection
+                Request::SubscribeToChainEvents => {
+                    let mut events = node_state.chain_events.subscribe();
+                    while let Ok(event) = events.recv().await {
+                        // A revoked token or address stops the stream at the next event
+                        grant(&access.lock().unwrap(), ip, token_hash.as_deref())
+                            .map_err(|e| anyhow!("{ip}: {e}"))?;
+                        stream
+                            .write_all(&Response::ChainEvent { event }.encode()?)
+                            .await?;
+                    }
+                    return Ok(false);
+                }
+                request => respond(request, &blockchain, &node_state).await?,
+            };
+            stream.write_all(&response.encode()?).await?;
+            Ok::<bool, anyhow::Error>(true)
+        }
+        .await;
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                warn!("API client error: {e}");
+                break;
+            }
+        }
+    }
+}
+
+async fn respond(
+    request: Request,
+    blockchain: &SharedBlockchain,
+    node_state: &SharedNodeState,
+) -> Result<Response, anyhow::Error> {
+    Ok(match request {
+        Request::Height => Response::Height {
+            height: blockchain.block_store().get_height() as u64,
+        },
+        Request::Block { block_hash } => Response::Block {
+            block: blockchain.block_store().get_block_by_hash(block_hash),
+        },
+        Request::BlockHash { height } => Response::BlockHash {
+            hash: blockchain
+                .block_store()
+                .get_block_hash_by_height(height as usize),
+        },
+        Request::Transaction { transaction_id } => Response::Transaction {
+            transaction: blockchain.block_store().get_transaction(transaction_id),
+        },
+        Request::TransactionAndInfo { transaction_id } => Response::TransactionAndInfo {
+            transaction_and_info: blockchain
+                .block_store()
+                .get_transaction_and_info(transaction_id),
+        },
+        Request::TransactionsOfAddress { address, page } => {
+            let start = (page * PAGE_SIZE) as usize;
+            let end = start + PAGE_SIZE as usize;
+
+            let mut transactions = Vec::with_capacity(PAGE_SIZE as usize);
+            let mut seen = 0usize;
+            let mut has_more = false;
+
+            'blocks: for block in blockchain.block_store().iter_blocks().rev() {
+                for tx in block?.transactions {
+                    if !tx.contains_address(address) {
+                        continue;
+                    }
+                    if seen >= start {
+                        transactions.push(tx.transaction_id.ok_or(TransactionError::MissingId)?);
+                    }
+                    seen += 1;
+                    if seen >= end {
+                        has_more = true;
+                        break 'blocks;
+                    }
+                }
+            }
+
+            Response::TransactionsOfAddress {
+                transactions,
+                next_page: has_more.then_some(page + 1),
+            }
+        }
+        Request::AvailableUTXOs { address, page } => {
+            let available = blockchain
+                .get_available_transaction_outputs(address)
+                .await?;
+            let available_inputs = slice_vec(
+                &available,
+                (page * PAGE_SIZE) as usize,
+                ((page + 1) * PAGE_SIZE) as usize,
+            )
+            .to_vec();
+            Response::AvailableUTXOs {
+                next_page: (available_inputs.len() == PAGE_SIZE as usize).then_some(page + 1),
+                available_inputs,
+            }
+        }
+        Request::Balance { address } => Response::Balance {
+            balance: blockchain.get_utxos().calculate_confirmed_balance(address),
+        },
+        Request::Reward => Response::Reward {
+            reward: get_block_reward(blockchain.block_store().get_height()),
+        },
+        Request::Peers => Response::Peers {
+            peers: node_state
+                .connected_peers
+                .read()
+                .await
+                .keys()
+                .copied()
+                .collect(),
+        },
+        Request::Mempool { page } => {
+            let mempool = node_state.mempool.get_mempool().await;
+            let mempool = slice_vec(
+                &mempool,
+                (page * PAGE_SIZE) as usize,
+                ((page + 1) * PAGE_SIZE) as usize,
+            )
+            .to_vec();
+            Response::Mempool {
+                next_page: (mempool.len() == PAGE_SIZE as usize).then_some(page + 1),
+                mempool,
+            }
+        }
+        Request::NewBlock { new_block } => Response::NewBlock {
+            status: accept_block(blockchain, node_state, new_block).await,
+        },
+        Request::NewTransaction { new_transaction } => Response::NewTransaction {
+            status: accept_transaction(blockchain, node_state, new_transaction).await,
+        },
+        Request::Difficulty => Response::Difficulty {
+            transaction_difficulty: blockchain.get_transaction_difficulty(),
+            block_difficulty: blockchain.get_block_difficulty(),
+        },
+        Request::BlockHeight { hash } => Response::BlockHeight {
+            height: blockchain.block_store().get_block_height_by_hash(hash),
+        },
+        Request::LiveTransactionDifficulty => Response::LiveTransactionDifficulty {
+            live_difficulty: calculate_live_transaction_difficulty(
+                &blockchain.get_transaction_difficulty(),
+                node_state.mempool.mempool_size().await,
+            ),
+        },
+        Request::SubscribeToChainEvents => {
+            return Err(anyhow!("Chain events are streamed by the connection"))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use snap_coin::{
+        core::{block::Block, transaction::Transaction},
+        crypto::address_inclusion_filter::AddressInclusionFilter,
+    };
+
+    use super::*;
+    use crate::connection_settings::ApiToken;
+
+    const LAN: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 20));
+    const OTHER: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 30));
+
+    fn access(require_token: bool) -> ApiAccess {
+        ApiAccess {
+            require_token,
+            tokens: vec![
+                ApiToken {
+                    name: "reader".to_string(),
+                    hash: hash_api_token("read"),
+                    permission: ApiPermission::ReadOnly,
+                    created: 0,
+                },
+                ApiToken {
+                    name: "miner".to_string(),
+                    hash: hash_api_token("submit"),
+                    permission: ApiPermission::Submit,
+                    created: 0,
+                },
+            ],
+            allowed_ips: [LAN].into(),
+        }
+    }
+
+    #[test]
+    fn loopback_is_always_allowed() {
+        let unknown = hash_api_token("unknown");
+        for ip in [
+            IpAddr::from([127, 0, 0, 1]),
+            IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1]),
+        ] {
+            assert_eq!(grant(&access(true), ip, None), Ok(ApiPermission::Submit));
+            assert_eq!(
+                grant(&access(true), ip, Some(&unknown)),
+                Ok(ApiPermission::Submit)
+            );
+        }
+    }
+
+    #[test]
+    fn allow_list_is_enforced() {
+        let token = hash_api_token("submit");
+        assert!(grant(&access(false), OTHER, Some(&token)).is_err());
+        assert_eq!(
+            grant(&access(false), LAN, Some(&token)),
+            Ok(ApiPermission::Submit)
+        );
+
+        let mut open = access(false);
+        open.allowed_ips.clear();
+        assert_eq!(grant(&open, OTHER, None), Ok(ApiPermission::ReadOnly));
+    }
+
+    #[test]
+    fn unknown_and_revoked_tokens_are_refused() {
+        assert!(grant(&access(false), LAN, Some(&hash_api_token("unknown"))).is_err());
+
+        let mut revoked = access(false);
+        revoked.tokens.retain(|token| token.name != "reader");
+        assert!(grant(&revoked, LAN, Some(&hash_api_token("read"))).is_err());
+    }
+
+    #[test]
+    fn required_token_must_be_presented() {
+        assert!(grant(&access(true), LAN, None).is_err());
+        assert_eq!(
+            grant(&access(false), LAN, None),
+            Ok(ApiPermission::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn token_grants_its_permission() {
+        assert_eq!(
+            grant(&access(true), LAN, Some(&hash_api_token("read"))),
+            Ok(ApiPermission::ReadOnly)
+        );
+        // Surrounding whitespace from pasting is ignored
+        assert_eq!(
+            grant(&access(true), LAN, Some(&hash_api_token(" submit\n"))),
+            Ok(ApiPermission::Submit)
+        );
+    }
+
+    #[test]
+    fn anonymous_never_exceeds_read_only_token() {
+        let mut open = access(false);
+        open.allowed_ips.clear();
+        for access in [access(false), open] {
+            assert_eq!(grant(&access, LAN, None), Ok(ApiPermission::ReadOnly));
+            assert_eq!(
+                grant(&access, LAN, Some(&hash_api_token("read"))),
+                Ok(ApiPermission::ReadOnly)
+            );
+        }
+    }
+
+    #[test]
+    fn read_only_may_not_submit() {
+        let new_transaction = Request::NewTransaction {
+            new_transaction: Transaction::new_transaction_now(vec![], vec![], &mut vec![]).unwrap(),
+        };
+        let new_block = Request::NewBlock {
+            new_block: Block::new_block_now(
+                vec![],
+                &[0; 32],
+                &[0; 32],
+                Hash::new_from_buf([0; 32]),
+                &[0; 32],
+                AddressInclusionFilter::create_filter(&[]).unwrap(),
+            ),
+        };
+
+        assert!(matches!(
+            refuse_read_only(ApiPermission::ReadOnly, &new_transaction),
+            Some(Response::NewTransaction { status: Err(_) })
+        ));
+        assert!(matches!(
+            refuse_read_only(ApiPermission::ReadOnly, &new_block),
+            Some(Response::NewBlock { status: Err(_) })
+        ));
+        assert!(refuse_read_only(ApiPermission::ReadOnly, &Request::Height).is_none());
+        assert!(refuse_read_only(ApiPermission::Submit, &new_transaction).is_none());
+        assert!(refuse_read_only(ApiPermission::Submit, &new_block).is_none());
+    }
+}
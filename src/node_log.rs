@@ -10,10 +10,10 @@ pub fn NodeLog(class: Option<String>) -> Element {
     let mut logs = use_signal(|| "No node logs yet...".to_string());
     let global_context = consume_context::<Signal<GlobalContext>>();
 
-    spawn(async move {
+    use_future(move || async move {
         loop {
             sleep(Duration::from_secs(2)).await;
-            if let Some(internal_node) = global_context().internal_node {
+            if let Some(internal_node) = global_context.peek().internal_node.clone() {
                 let log = match fs::read_to_string(internal_node.log_file.clone()).await {
                     Ok(log) => log,
                     Err(e) => {
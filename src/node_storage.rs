@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Error};
+
+/// Free space on the data volume below which the `Connection` screen warns
+pub const LOW_SPACE_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Where the in-wallet node keeps its data unless another directory was chosen
+pub fn default_node_dir() -> Result<PathBuf, Error> {
+    let home = dirs::home_dir().ok_or_else(|| Error::msg("Could not determine home directory"))?;
+    Ok(home.join("node-mainnet"))
+}
+
+/// Size of everything below a path, zero if it does not exist
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Space taken by a node data directory, and left on its volume
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DiskUsage {
+    pub blocks: u64,
+    pub logs: u64,
+    /// None when the volume could not be queried
+    pub free: Option<u64>,
+}
+
+impl DiskUsage {
+    pub fn low_space(&self) -> bool {
+        self.free.is_some_and(|free| free < LOW_SPACE_BYTES)
+    }
+}
+
+/// Measure a node data directory, which does not need to exist yet
+pub fn disk_usage(node_dir: &Path) -> DiskUsage {
+    // Free space is asked of the closest directory that exists, the volume the node dir will land on
+    let free = node_dir
+        .ancestors()
+        .find(|dir| dir.exists())
+        .and_then(|dir| fs2::available_space(dir).ok());
+    DiskUsage {
+        blocks: dir_size(&node_dir.join("blockchain")),
+        logs: dir_size(&node_dir.join("logs")),
+        free,
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Whether a directory holds node data worth moving
+pub fn has_node_data(node_dir: &Path) -> bool {
+    node_dir.join("blockchain").exists()
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move the node data from one directory to another, which must not hold node data yet
+/// Blocking, and the node must not be running
+pub fn move_node_data(from: &Path, to: &Path) -> Result<(), Error> {
+    if from == to {
+        return Ok(());
+    }
+    if to.starts_with(from) {
+        return Err(anyhow!(
+            "The new directory can not be inside the current one"
+        ));
+    }
+    if has_node_data(to) {
+        return Err(anyhow!("{} already holds node data", to.display()));
+    }
+
+    for part in ["blockchain", "logs"] {
+        let source = from.join(part);
+        if !source.exists() {
+            continue;
+        }
+        let target = to.join(part);
+        fs::create_dir_all(to)?;
+        // Renaming fails across volumes, where the data is copied instead
+        if fs::rename(&source, &target).is_err() {
+            if let Err(e) = copy_dir(&source, &target) {
+                let _ = fs::remove_dir_all(&target);
+                return Err(e);
+            }
+            fs::remove_dir_all(&source)?;
+        }
+    }
+    Ok(())
+}
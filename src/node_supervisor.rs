@@ -0,0 +1,812 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use dioxus::prelude::*;
+use log::info;
+use snap_coin::{
+    api::client::Client,
+    core::blockchain::Blockchain,
+    full_node::{
+        auto_peer::start_auto_peer, auto_reconnect::start_auto_reconnect, connect_peer,
+        create_full_node, ibd::ibd_blockchain, node_state::SharedNodeState, SharedBlockchain,
+    },
+    node::{
+        message::{Command, Message},
+        peer::PeerHandle,
+    },
+};
+use tokio::{
+    net::lookup_host,
+    sync::{watch, Notify},
+    task::JoinSet,
+    time::{sleep, timeout},
+};
+
+use crate::{
+    connection_settings::{ApiAccess, NodeOptions},
+    node_api::{bind_api, serve_api},
+    GlobalContext, NodeHandle,
+};
+
+/// How long a stopping node gets to finish its current block before its tasks are dropped
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// How often the sync progress is measured
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+/// Peers are asked for their height every this many progress ticks
+const PEER_HEIGHT_EVERY: u32 = 5;
+/// How often the peer lists are enforced
+const PEER_LIST_INTERVAL: Duration = Duration::from_secs(10);
+/// How long connecting to a single peer may take
+pub const PEER_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// First and longest wait before dialing failed bootstrap peers again
+const BOOTSTRAP_RETRY_MIN: Duration = Duration::from_secs(15);
+const BOOTSTRAP_RETRY_MAX: Duration = Duration::from_secs(5 * 60);
+/// How long a new node waits for the previous one to let go of the data directory
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where the in-wallet node is in its life
+#[derive(Clone, PartialEq, Debug)]
+pub enum NodeLifecycle {
+    Starting,
+    Syncing,
+    Synced,
+    Stopping,
+    Stopped,
+    Failed(String),
+}
+
+impl NodeLifecycle {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeLifecycle::Starting => "Starting",
+            NodeLifecycle::Syncing => "Syncing",
+            NodeLifecycle::Synced => "Synced",
+            NodeLifecycle::Stopping => "Stopping",
+            NodeLifecycle::Stopped => "Stopped",
+            NodeLifecycle::Failed(_) => "Failed",
+        }
+    }
+
+    /// Whether the node thread has exited
+    pub fn is_finished(&self) -> bool {
+        matches!(self, NodeLifecycle::Stopped | NodeLifecycle::Failed(_))
+    }
+}
+
+/// How far the in-wallet node is behind the network
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SyncProgress {
+    pub local_height: usize,
+    /// Highest chain any connected peer reported, none until one answered
+    pub best_peer_height: Option<usize>,
+    pub blocks_per_second: f64,
+    /// Size of the blocks stored since the node started
+    pub bytes_downloaded: u64,
+}
+
+impl SyncProgress {
+    pub fn remaining(&self) -> usize {
+        self.best_peer_height
+            .map_or(0, |best| best.saturating_sub(self.local_height))
+    }
+
+    /// Share of the best peer chain the node has, 1 before any peer answered
+    pub fn fraction(&self) -> f64 {
+        match self.best_peer_height {
+            Some(best) if best > 0 => (self.local_height as f64 / best as f64).min(1.0),
+            _ => 1.0,
+        }
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.remaining();
+        if remaining == 0 || self.blocks_per_second <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            remaining as f64 / self.blocks_per_second,
+        ))
+    }
+}
+
+/// What the in-wallet node last measured of a connected peer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PeerStats {
+    pub latency: Duration,
+    pub height: usize,
+}
+
+/// What came of dialing a configured peer
+#[derive(Clone, PartialEq, Debug)]
+pub enum PeerOutcome {
+    Connected(SocketAddr),
+    Denied,
+    Failed(String),
+}
+
+impl PeerOutcome {
+    pub fn describe(&self) -> String {
+        match self {
+            PeerOutcome::Connected(address) => format!("Connected to {address}"),
+            PeerOutcome::Denied => "On the deny list".to_string(),
+            PeerOutcome::Failed(e) => e.clone(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PeerReport {
+    /// The peer as configured
+    pub peer: String,
+    pub outcome: PeerOutcome,
+}
+
+/// What the in-wallet node is started with, kept to restart it the same way
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeConfig {
+    pub peers: Vec<String>,
+    pub options: NodeOptions,
+    /// Peers kept connected for as long as the node runs
+    pub allowed_peers: Vec<String>,
+    /// Addresses the node never stays connected to
+    pub denied_peers: BTreeSet<IpAddr>,
+    pub api_access: ApiAccess,
+}
+
+struct Shared {
+    config: NodeConfig,
+    lifecycle: watch::Sender<NodeLifecycle>,
+    stop_requested: AtomicBool,
+    stop: Notify,
+    /// The chain of the node, alive until every handle to it was dropped
+    chain: Mutex<Weak<Blockchain>>,
+    progress: watch::Sender<SyncProgress>,
+    peer_stats: Mutex<HashMap<SocketAddr, PeerStats>>,
+    bootstrap: Mutex<Vec<PeerReport>>,
+    /// The peer lists in effect, changed at runtime from the peers screen
+    allowed_peers: Mutex<Vec<String>>,
+    denied_peers: Mutex<BTreeSet<IpAddr>>,
+    /// Where the API listens, none until it is bound
+    api_address: Mutex<Option<SocketAddr>>,
+    /// Shared with the API connections, which check it on every request
+    api_access: Arc<Mutex<ApiAccess>>,
+}
+
+/// Every node started during this run, so a new node waits for the previous one and the app can stop them on exit
+static SUPERVISORS: Mutex<Vec<NodeSupervisor>> = Mutex::new(Vec::new());
+
+/// Whether the logs of a previous run were cleared, only done before the first node of this run
+static LOGS_CLEARED: AtomicBool = AtomicBool::new(false);
+
+/// Owns the thread and Tokio runtime of an in-wallet node
+#[derive(Clone)]
+pub struct NodeSupervisor {
+    shared: Arc<Shared>,
+}
+
+impl NodeSupervisor {
+    /// Start a node on its own thread, the handle is sent once the chain is opened
+    pub fn start(
+        config: NodeConfig,
+    ) -> (
+        NodeSupervisor,
+        futures_channel::oneshot::Receiver<NodeHandle>,
+    ) {
+        let supervisor = NodeSupervisor {
+            shared: Arc::new(Shared {
+                allowed_peers: Mutex::new(config.allowed_peers.clone()),
+                denied_peers: Mutex::new(config.denied_peers.clone()),
+                api_access: Arc::new(Mutex::new(config.api_access.clone())),
+                config,
+                lifecycle: watch::Sender::new(NodeLifecycle::Starting),
+                stop_requested: AtomicBool::new(false),
+                stop: Notify::new(),
+                chain: Mutex::new(Weak::new()),
+                progress: watch::Sender::new(SyncProgress::default()),
+                peer_stats: Mutex::new(HashMap::new()),
+                bootstrap: Mutex::new(vec![]),
+                api_address: Mutex::new(None),
+            }),
+        };
+
+        let previous = {
+            let mut supervisors = SUPERVISORS.lock().unwrap();
+            supervisors.retain(|supervisor| !supervisor.released());
+            let previous = supervisors.clone();
+            supervisors.push(supervisor.clone());
+            previous
+        };
+
+        let (ready_tx, ready_rx) = futures_channel::oneshot::channel();
+        let thread_supervisor = supervisor.clone();
+        std::thread::spawn(move || {
+            // The node panics on some failures, which still have to end up in the lifecycle
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                thread_supervisor.run(&previous, ready_tx)
+            }));
+            thread_supervisor
+                .shared
+                .lifecycle
+                .send_replace(match result {
+                    Ok(Ok(())) => NodeLifecycle::Stopped,
+                    Ok(Err(e)) => NodeLifecycle::Failed(e.to_string()),
+                    Err(_) => NodeLifecycle::Failed("The node crashed, see its logs".to_string()),
+                });
+        });
+
+        (supervisor, ready_rx)
+    }
+
+    pub fn lifecycle(&self) -> NodeLifecycle {
+        self.shared.lifecycle.borrow().clone()
+    }
+
+    pub fn progress(&self) -> SyncProgress {
+        *self.shared.progress.borrow()
+    }
+
+    pub fn peer_stats(&self) -> HashMap<SocketAddr, PeerStats> {
+        self.shared.peer_stats.lock().unwrap().clone()
+    }
+
+    /// How dialing each configured peer went, empty until the node got to it
+    pub fn bootstrap_report(&self) -> Vec<PeerReport> {
+        self.shared.bootstrap.lock().unwrap().clone()
+    }
+
+    pub fn allowed_peers(&self) -> Vec<String> {
+        self.shared.allowed_peers.lock().unwrap().clone()
+    }
+
+    pub fn denied_peers(&self) -> BTreeSet<IpAddr> {
+        self.shared.denied_peers.lock().unwrap().clone()
+    }
+
+    /// Replace the peer lists of the running node, they are enforced on its next round
+    pub fn set_peer_lists(&self, allowed: Vec<String>, denied: BTreeSet<IpAddr>) {
+        *self.shared.allowed_peers.lock().unwrap() = allowed;
+        *self.shared.denied_peers.lock().unwrap() = denied;
+    }
+
+    pub fn api_address(&self) -> Option<SocketAddr> {
+        *self.shared.api_address.lock().unwrap()
+    }
+
+    pub fn api_access(&self) -> ApiAccess {
+        self.shared.api_access.lock().unwrap().clone()
+    }
+
+    /// Replace who may use the API, connections already open are held to it from their next request
+    pub fn set_api_access(&self, access: ApiAccess) {
+        *self.shared.api_access.lock().unwrap() = access;
+    }
+
+    pub fn config(&self) -> &NodeConfig {
+        &self.shared.config
+    }
+
+    /// Ask the node to shut down, see `stopped` to wait for it
+    pub fn stop(&self) {
+        self.shared.stop_requested.store(true, Ordering::SeqCst);
+        self.shared.stop.notify_one();
+    }
+
+    /// Wait until the node reached a state matching `reached`, or its thread exited
+    pub async fn wait_for(&self, reached: impl Fn(&NodeLifecycle) -> bool) -> NodeLifecycle {
+        let mut lifecycle = self.shared.lifecycle.subscribe();
+        let state = lifecycle
+            .wait_for(|state| reached(state) || state.is_finished())
+            .await
+            .map(|state| state.clone());
+        state.unwrap_or_else(|_| self.lifecycle())
+    }
+
+    pub async fn stopped(&self) -> NodeLifecycle {
+        self.wait_for(NodeLifecycle::is_finished).await
+    }
+
+    /// Whether the node exited and nothing holds on to its chain anymore
+    fn released(&self) -> bool {
+        self.lifecycle().is_finished() && self.shared.chain.lock().unwrap().strong_count() == 0
+    }
+
+    fn run(
+        &self,
+        previous: &[NodeSupervisor],
+        ready: futures_channel::oneshot::Sender<NodeHandle>,
+    ) -> Result<(), anyhow::Error> {
+        // Opening the chain while another node still has it open would fail on its database lock
+        let deadline = Instant::now() + RELEASE_TIMEOUT;
+        while !previous.iter().all(NodeSupervisor::released) {
+            if self.shared.stop_requested.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            if Instant::now() > deadline {
+                return Err(anyhow!("The previous node did not let go of its data"));
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        let config = &self.shared.config;
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        let node_path = config.options.data_dir()?;
+        let node_path = node_path
+            .to_str()
+            .ok_or(anyhow!(
+                "The node data directory {} is not a valid path",
+                node_path.display()
+            ))?
+            .to_string();
+
+        // The node logger is set up once per process and keeps its file open, later nodes log into it too
+        if !LOGS_CLEARED.swap(true, Ordering::SeqCst) && fs::exists(node_path.clone() + "/logs/")? {
+            fs::remove_dir_all(node_path.clone() + "/logs/")?;
+            fs::create_dir_all(node_path.clone() + "/logs/")?;
+        }
+
+        let (blockchain, node_state, log_file) = {
+            let _runtime = rt.enter();
+            create_full_node(&node_path, false, None)
+        };
+        *self.shared.chain.lock().unwrap() = Arc::downgrade(&blockchain);
+
+        let _ = ready.send(NodeHandle {
+            node_state: node_state.clone(),
+            blockchain: blockchain.clone(),
+            log_file,
+            runtime: rt.handle().clone(),
+            supervisor: self.clone(),
+        });
+
+        let result = rt.block_on(async {
+            tokio::select! {
+                result = self.run_node(&blockchain, &node_state) => result,
+                _ = self.shared.stop.notified() => Ok(()),
+            }
+        });
+
+        self.shared.lifecycle.send_replace(NodeLifecycle::Stopping);
+
+        // Wait for the block being processed, and hold the lock so no other one starts while the tasks are dropped
+        let processing = rt.block_on(timeout(GRACE_PERIOD, node_state.processing.lock()));
+        rt.shutdown_timeout(GRACE_PERIOD);
+        drop(processing);
+
+        // The block index is flushed when its last handle drops, blocks themselves are written atomically
+        blockchain.get_utxos().db.flush()?;
+
+        result
+    }
+
+    async fn run_node(
+        &self,
+        blockchain: &SharedBlockchain,
+        node_state: &SharedNodeState,
+    ) -> Result<(), anyhow::Error> {
+        let config = &self.shared.config;
+        let hashing_threads = config.options.hashing_threads();
+
+        let denied = self.denied_peers();
+        let report = bootstrap(&config.peers, &denied, blockchain, node_state).await;
+        *self.shared.bootstrap.lock().unwrap() = report.clone();
+
+        let resolved_peers: Vec<SocketAddr> = report
+            .iter()
+            .filter_map(|report| match report.outcome {
+                PeerOutcome::Connected(address) => Some(address),
+                _ => None,
+            })
+            .collect();
+        // One reachable peer is enough to sync, the others are retried in the background
+        if resolved_peers.is_empty() && !config.peers.is_empty() {
+            let failures: Vec<String> = report
+                .iter()
+                .map(|report| format!("{}: {}", report.peer, report.outcome.describe()))
+                .collect();
+            return Err(anyhow!("Could not reach any peer. {}", failures.join(", ")));
+        }
+        tokio::spawn(retry_bootstrap(
+            self.clone(),
+            blockchain.clone(),
+            node_state.clone(),
+        ));
+
+        // Auto peer skips reserved addresses, denials made later are enforced by `enforce_peer_lists`
+        let auto_peer = start_auto_peer(
+            node_state.clone(),
+            blockchain.clone(),
+            denied.into_iter().collect(),
+        );
+        tokio::spawn(monitor_progress(
+            self.clone(),
+            blockchain.clone(),
+            node_state.clone(),
+        ));
+        tokio::spawn(enforce_peer_lists(
+            self.clone(),
+            blockchain.clone(),
+            node_state.clone(),
+        ));
+
+        let listener =
+            bind_api(config.options.api_bind, config.options.preferred_api_port()).await?;
+        *self.shared.api_address.lock().unwrap() = Some(listener.local_addr()?);
+        tokio::spawn(serve_api(
+            listener,
+            blockchain.clone(),
+            node_state.clone(),
+            self.shared.api_access.clone(),
+        ));
+
+        if !resolved_peers.is_empty() {
+            self.shared.lifecycle.send_replace(NodeLifecycle::Syncing);
+            *node_state.is_syncing.write().await = true;
+
+            sleep(Duration::from_secs(2)).await;
+
+            let _auto_reconnect = start_auto_reconnect(
+                node_state.clone(),
+                blockchain.clone(),
+                resolved_peers,
+                config.options.full_ibd,
+                hashing_threads,
+            );
+
+            ibd_blockchain(
+                node_state.clone(),
+                blockchain.clone(),
+                config.options.full_ibd,
+                hashing_threads,
+            )
+            .await
+            .map_err(|e| anyhow!("Initial block download failed: {e}"))?;
+            info!("Initial block download finished");
+
+            *node_state.is_syncing.write().await = false;
+        }
+        self.shared.lifecycle.send_replace(NodeLifecycle::Synced);
+
+        auto_peer.await?;
+
+        Ok(())
+    }
+}
+
+/// Keep the sync progress of a node up to date, for as long as its runtime lives
+async fn monitor_progress(
+    supervisor: NodeSupervisor,
+    blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+) {
+    let started = blockchain.block_store().get_height();
+    let mut measured = started;
+    let mut last_tick = Instant::now();
+    let mut ticks = 0;
+    let mut progress = SyncProgress {
+        local_height: started,
+        ..SyncProgress::default()
+    };
+
+    loop {
+        sleep(PROGRESS_INTERVAL).await;
+        let local_height = blockchain.block_store().get_height();
+
+        // Rate is smoothed, blocks arrive in batches
+        let elapsed = last_tick.elapsed().as_secs_f64();
+        last_tick = Instant::now();
+        let rate = local_height.saturating_sub(progress.local_height) as f64 / elapsed;
+        progress.blocks_per_second = if progress.blocks_per_second == 0.0 {
+            rate
+        } else {
+            progress.blocks_per_second * 0.7 + rate * 0.3
+        };
+
+        // Blocks are stored one file per height
+        let store_path = &blockchain.block_store().store_path;
+        while measured < local_height {
+            progress.bytes_downloaded += fs::metadata(format!("{store_path}{measured}.dat"))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            measured += 1;
+        }
+        progress.local_height = local_height;
+
+        if ticks % PEER_HEIGHT_EVERY == 0 {
+            let peers: Vec<_> = node_state
+                .connected_peers
+                .read()
+                .await
+                .values()
+                .cloned()
+                .collect();
+            let mut best = None;
+            let mut stats = HashMap::new();
+            for peer in peers {
+                let sent = Instant::now();
+                let pong = timeout(
+                    PROGRESS_INTERVAL,
+                    peer.request(Message::new(Command::Ping {
+                        height: local_height,
+                    })),
+                )
+                .await;
+                if let Ok(Ok(Message {
+                    command: Command::Pong { height },
+                    ..
+                })) = pong
+                {
+                    best = best.max(Some(height));
+                    stats.insert(
+                        peer.address,
+                        PeerStats {
+                            latency: sent.elapsed(),
+                            height,
+                        },
+                    );
+                }
+            }
+            if best.is_some() {
+                progress.best_peer_height = best;
+            }
+            *supervisor.shared.peer_stats.lock().unwrap() = stats;
+        }
+        ticks += 1;
+
+        supervisor.shared.progress.send_replace(progress);
+    }
+}
+
+/// Resolve and connect a configured peer, within `PEER_CONNECT_TIMEOUT`
+async fn dial(
+    peer: &str,
+    denied: &BTreeSet<IpAddr>,
+    blockchain: &SharedBlockchain,
+    node_state: &SharedNodeState,
+) -> PeerOutcome {
+    let dialed = timeout(PEER_CONNECT_TIMEOUT, async {
+        let address = lookup_host(peer)
+            .await
+            .map_err(|e| format!("Could not resolve: {e}"))?
+            .next()
+            .ok_or("Could not resolve".to_string())?;
+        if denied.contains(&address.ip()) {
+            return Ok(PeerOutcome::Denied);
+        }
+        connect_peer(address, blockchain, node_state)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>(PeerOutcome::Connected(address))
+    })
+    .await;
+
+    match dialed {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => PeerOutcome::Failed(e),
+        Err(_) => PeerOutcome::Failed("Timed out".to_string()),
+    }
+}
+
+/// Dial every configured peer at once
+async fn bootstrap(
+    peers: &[String],
+    denied: &BTreeSet<IpAddr>,
+    blockchain: &SharedBlockchain,
+    node_state: &SharedNodeState,
+) -> Vec<PeerReport> {
+    let mut dials = JoinSet::new();
+    for (i, peer) in peers.iter().enumerate() {
+        let (peer, denied, blockchain, node_state) = (
+            peer.clone(),
+            denied.clone(),
+            blockchain.clone(),
+            node_state.clone(),
+        );
+        dials.spawn(async move {
+            let outcome = dial(&peer, &denied, &blockchain, &node_state).await;
+            (i, PeerReport { peer, outcome })
+        });
+    }
+
+    let mut reports = dials.join_all().await;
+    reports.sort_by_key(|(i, _)| *i);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+/// Keep dialing configured peers that failed, backing off between rounds
+async fn retry_bootstrap(
+    supervisor: NodeSupervisor,
+    blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+) {
+    let mut delay = BOOTSTRAP_RETRY_MIN;
+    loop {
+        let failed: Vec<String> = supervisor
+            .bootstrap_report()
+            .into_iter()
+            .filter(|report| matches!(report.outcome, PeerOutcome::Failed(_)))
+            .map(|report| report.peer)
+            .collect();
+        if failed.is_empty() {
+            return;
+        }
+
+        sleep(delay).await;
+        delay = (delay * 2).min(BOOTSTRAP_RETRY_MAX);
+
+        let retried = bootstrap(
+            &failed,
+            &supervisor.denied_peers(),
+            &blockchain,
+            &node_state,
+        )
+        .await;
+        let mut report = supervisor.shared.bootstrap.lock().unwrap();
+        for retry in retried {
+            if let Some(entry) = report.iter_mut().find(|entry| entry.peer == retry.peer) {
+                *entry = retry;
+            }
+        }
+    }
+}
+
+/// Drop peers on the deny list and reconnect the ones on the allow list
+async fn enforce_peer_lists(
+    supervisor: NodeSupervisor,
+    blockchain: SharedBlockchain,
+    node_state: SharedNodeState,
+) {
+    loop {
+        let denied = supervisor.denied_peers();
+        let connected: Vec<PeerHandle> = node_state
+            .connected_peers
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect();
+        for peer in &connected {
+            if denied.contains(&peer.address.ip()) {
+                let _ = peer.kill("Peer is on the deny list".to_string()).await;
+            }
+        }
+
+        for address in supervisor.allowed_peers() {
+            let Ok(Some(address)) = lookup_host(&address)
+                .await
+                .map(|mut addresses| addresses.next())
+            else {
+                continue;
+            };
+            if denied.contains(&address.ip())
+                || connected.iter().any(|peer| peer.address == address)
+            {
+                continue;
+            }
+            let _ = timeout(
+                PEER_CONNECT_TIMEOUT,
+                connect_peer(address, &blockchain, &node_state),
+            )
+            .await;
+        }
+
+        sleep(PEER_LIST_INTERVAL).await;
+    }
+}
+
+/// Start the in-wallet node and connect the wallet to its API once it listens
+pub async fn launch_node(
+    mut global: Signal<GlobalContext>,
+    config: NodeConfig,
+) -> Result<NodeSupervisor, String> {
+    let (supervisor, ready) = NodeSupervisor::start(config);
+
+    let Ok(handle) = ready.await else {
+        return Err(match supervisor.stopped().await {
+            NodeLifecycle::Failed(e) => e,
+            _ => "The node stopped before it started".to_string(),
+        });
+    };
+    global.write().internal_node = Some(handle);
+
+    let client = loop {
+        let connected = match supervisor.api_address() {
+            // Listening on every interface includes loopback
+            Some(address) => {
+                Client::connect(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), address.port()))
+                    .await
+                    .ok()
+            }
+            None => None,
+        };
+        match connected {
+            Some(c) => break c,
+            None => {
+                if let NodeLifecycle::Failed(e) = supervisor.lifecycle() {
+                    return Err(e);
+                }
+                if supervisor.lifecycle().is_finished() {
+                    return Err("The node stopped before its API was up".to_string());
+                }
+                sleep(Duration::from_millis(200)).await
+            }
+        }
+    };
+    global.with_mut(|g| {
+        g.api_client = Some(Arc::new(client));
+        g.api_token = None;
+    });
+
+    Ok(supervisor)
+}
+
+/// Stop the in-wallet node, and disconnect the wallet from it once it is down
+pub async fn stop_node(mut global: Signal<GlobalContext>) -> NodeLifecycle {
+    let Some(supervisor) = global
+        .peek()
+        .internal_node
+        .as_ref()
+        .map(|node| node.supervisor.clone())
+    else {
+        return NodeLifecycle::Stopped;
+    };
+
+    global.write().api_client = None;
+    supervisor.stop();
+    let state = supervisor.stopped().await;
+    global.write().internal_node = None;
+    state
+}
+
+/// Stop the in-wallet node and start it again with the same configuration
+pub async fn restart_node(global: Signal<GlobalContext>) -> Result<NodeSupervisor, String> {
+    let Some(config) = global.peek().internal_node.as_ref().map(|node| {
+        let mut options = node.supervisor.config().options.clone();
+        // Keep the address external tools already use
+        if let Some(address) = node.supervisor.api_address() {
+            options.last_api_port = Some(address.port());
+        }
+        NodeConfig {
+            options,
+            allowed_peers: node.supervisor.allowed_peers(),
+            denied_peers: node.supervisor.denied_peers(),
+            api_access: node.supervisor.api_access(),
+            ..node.supervisor.config().clone()
+        }
+    }) else {
+        return Err("The in-wallet node is not running".to_string());
+    };
+
+    stop_node(global).await;
+    launch_node(global, config).await
+}
+
+/// Stop every node of this run and wait for them, blocking, as the app exits
+#[cfg(feature = "desktop")]
+pub fn shutdown_all() {
+    let supervisors = SUPERVISORS.lock().unwrap().clone();
+    for supervisor in &supervisors {
+        supervisor.stop();
+    }
+
+    let deadline = Instant::now() + GRACE_PERIOD * 2;
+    while Instant::now() < deadline
+        && !supervisors
+            .iter()
+            .all(|supervisor| supervisor.lifecycle().is_finished())
+    {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+
+use dioxus::prelude::*;
+use notify_rust::Notification;
+use snap_coin::{core::transaction::TransactionId, crypto::keys::Public, to_snap};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    sync::{SyncEvent, SyncState},
+    GlobalContext,
+};
+
+/// Name of a vault wallet, or a shortened address for everyone else
+fn label(address: &Public, labels: &HashMap<Public, String>) -> String {
+    labels.get(address).cloned().unwrap_or_else(|| {
+        let address = address.dump_base36();
+        format!("{}...", &address[..8.min(address.len())])
+    })
+}
+
+/// Show a desktop notification, best effort, a missing notification daemon must not break the wallet
+fn show(summary: String, body: String) {
+    tokio::task::spawn_blocking(move || {
+        let _ = Notification::new()
+            .appname("Snap Coin Wallet")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
+
+/// Raise desktop notifications for vault activity, called once by the app root
+pub fn use_notifications(global: Signal<GlobalContext>, sync: Signal<SyncState>) {
+    use_future(move || async move {
+        let mut events = sync.peek().subscribe();
+        // Incoming payments show up twice with an in-wallet node, once in the mempool and once confirmed
+        let mut notified: HashSet<(Public, TransactionId)> = HashSet::new();
+
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let global = global.peek();
+
+            match event {
+                SyncEvent::Transaction {
+                    wallet,
+                    transaction_id,
+                    transaction,
+                    confirmed,
+                } => {
+                    if !global.settings.notifications_enabled(&wallet) {
+                        continue;
+                    }
+                    let labels = global.wallet_names();
+                    let Some(name) = labels.get(&wallet) else {
+                        continue;
+                    };
+
+                    let is_send = transaction
+                        .inputs
+                        .iter()
+                        .any(|input| input.output_owner == wallet);
+                    if is_send {
+                        if !confirmed || !notified.insert((wallet, transaction_id)) {
+                            continue;
+                        }
+                        let sent = transaction
+                            .outputs
+                            .iter()
+                            .filter(|output| output.receiver != wallet)
+                            .map(|output| output.amount)
+                            .sum::<u64>();
+                        let to = transaction
+                            .outputs
+                            .iter()
+                            .find(|output| output.receiver != wallet)
+                            .map_or(name.clone(), |output| label(&output.receiver, &labels));
+                        show(
+                            "Transaction confirmed".to_string(),
+                            format!("{name} sent {} SNAP to {to}", to_snap(sent)),
+                        );
+                    } else {
+                        if !notified.insert((wallet, transaction_id)) {
+                            continue;
+                        }
+                        let received = transaction
+                            .outputs
+                            .iter()
+                            .filter(|output| output.receiver == wallet)
+                            .map(|output| output.amount)
+                            .sum::<u64>();
+                        let from = transaction
+                            .inputs
+                            .first()
+                            .map_or("network".to_string(), |input| {
+                                label(&input.output_owner, &labels)
+                            });
+                        show(
+                            if confirmed {
+                                "Payment received".to_string()
+                            } else {
+                                "Incoming payment".to_string()
+                            },
+                            format!("{name} received {} SNAP from {from}", to_snap(received)),
+                        );
+                    }
+                }
+                SyncEvent::NodeSynced => {
+                    if global.settings.notify_node_synced {
+                        show(
+                            "Node synced".to_string(),
+                            "The in-wallet node caught up with the network".to_string(),
+                        );
+                    }
+                }
+                SyncEvent::TransactionExpired { .. } => {}
+            }
+        }
+    });
+}
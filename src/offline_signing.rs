@@ -0,0 +1,419 @@
+use anyhow::anyhow;
+use dioxus::prelude::*;
+use rfd::FileDialog;
+use snap_coin::{
+    core::transaction::TransactionInput, crypto::keys::Public, economics::EXPIRATION_TIME,
+    to_nano, to_snap,
+};
+
+use crate::{
+    authorize::ask_for_auth,
+    copy_box::CopyBox,
+    home::format_timestamp_secs,
+    transaction_file::{
+        build_unsigned_transaction, sign_transaction, verify_signatures, SignatureStatus,
+        TransactionFile, TransactionFileKind,
+    },
+    GlobalContext,
+};
+
+fn pick_transaction_file(title: &str) -> Option<std::path::PathBuf> {
+    FileDialog::new()
+        .set_title(title)
+        .add_filter("Transaction", &["json"])
+        .pick_file()
+}
+
+fn save_transaction_file(
+    file: &TransactionFile,
+    title: &str,
+    name: &str,
+) -> Result<bool, anyhow::Error> {
+    if let Some(path) = FileDialog::new()
+        .set_title(title)
+        .set_file_name(name)
+        .add_filter("Transaction", &["json"])
+        .save_file()
+    {
+        file.save(&path)?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+#[component]
+pub fn OfflineSigning() -> Element {
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Offline Signing" }
+            p {
+                "Build a transaction on an online, watch-only wallet, sign it on a wallet that never goes online, then bring the signed file back to broadcast it."
+            }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+                BuildUnsigned {}
+                SignOffline {}
+                BroadcastSigned {}
+            }
+        }
+    }
+}
+
+/// Shows the inputs and outputs of a transaction file for review
+#[component]
+pub fn TransactionReview(file: TransactionFile) -> Element {
+    let sender = file.transaction.inputs.first().map(|i| i.output_owner);
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-3 text-sm",
+            p { class: "text-neutral-400", "Created {format_timestamp_secs(file.transaction.timestamp)}" }
+            if file.is_expired() {
+                p { class: "text-red-400! font-bold", "Expired {format_timestamp_secs(file.expires_at())}, nodes no longer accept it" }
+            } else if file.kind == TransactionFileKind::Signed {
+                p { class: "text-yellow-400", "Broadcast before {format_timestamp_secs(file.expires_at())}" }
+            } else {
+                p { class: "text-neutral-400", "Signing stamps it again, it must be broadcast within {EXPIRATION_TIME / 60} minutes of signing" }
+            }
+
+            h4 { "Inputs" }
+            for (input, amount) in file.transaction.inputs.iter().zip(file.input_amounts.iter()) {
+                div {
+                    class: "bg-neutral-800 p-2 rounded flex flex-col gap-2",
+                    CopyBox { class: "w-full min-w-0", text: input.output_owner.dump_base36(), title: "Input owner" }
+                    span { class: "font-mono text-red-400", "-{to_snap(*amount)} SNAP" }
+                }
+            }
+
+            h4 { "Outputs" }
+            for output in file.transaction.outputs.iter() {
+                div {
+                    class: "bg-neutral-800 p-2 rounded flex flex-col gap-2",
+                    CopyBox { class: "w-full min-w-0", text: output.receiver.dump_base36(), title: "Receiver" }
+                    span {
+                        class: "font-mono text-green-400",
+                        "+{to_snap(output.amount)} SNAP"
+                        if Some(output.receiver) == sender { " (change)" }
+                    }
+                }
+            }
+
+            p {
+                class: "font-mono",
+                "Total in: {to_snap(file.total_in())} SNAP · Total out: {to_snap(file.total_out())} SNAP"
+            }
+            if file.total_in() != file.total_out() {
+                p { class: "text-red-400! font-bold", "Inputs and outputs do not add up!" }
+            }
+        }
+    }
+}
+
+/// Step 1, on an online (watch-only) wallet
+#[component]
+fn BuildUnsigned() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut source = use_signal(|| "".to_string());
+    let mut recipients: Signal<Vec<(String, String)>> =
+        use_signal(|| vec![(String::new(), String::new())]);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_building = use_signal(|| false);
+    // Spent by files built here that are not on chain yet, so the next file does not spend them again
+    let mut ignore_inputs: Signal<Vec<TransactionInput>> = use_signal(Vec::new);
+
+    rsx! {
+        div {
+            class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+            h3 { class: "font-semibold text-lg", "1. Build (online)" }
+
+            if global().api_client.is_none() {
+                p { class: "text-neutral-400", "Connect to a node to build unsigned transactions." }
+            } else {
+                input {
+                    class: "bg-neutral-800 p-2 rounded w-full",
+                    placeholder: "Watch-only source address",
+                    value: "{source}",
+                    oninput: move |e| source.set(e.value())
+                }
+
+                for (idx, (addr, amt)) in recipients.read().iter().enumerate() {
+                    div { class: "flex gap-2",
+                        div {
+                            class: "flex flex-col gap-3 w-full",
+                            input {
+                                class: "bg-neutral-800 p-2 rounded flex-1 w-full",
+                                placeholder: "Recipient address",
+                                value: "{addr}",
+                                oninput: move |e| {
+                                    recipients.with_mut(|r| r[idx].0 = e.value());
+                                }
+                            }
+                            input {
+                                class: "bg-neutral-800 p-2 rounded w-full font-mono font-bold",
+                                type: "number",
+                                placeholder: "Amount",
+                                value: "{amt}",
+                                oninput: move |e| {
+                                    recipients.with_mut(|r| r[idx].1 = e.value());
+                                }
+                            }
+                        }
+
+                        if recipients.read().len() > 1 {
+                            button {
+                                class: "px-2 text-red-400 bg-neutral-900! border! border-[var(--border)]!",
+                                onclick: move |_| {
+                                    recipients.with_mut(|r| { r.remove(idx); });
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+
+                button {
+                    class: "text-sm text-indigo-400 self-start",
+                    onclick: move |_| {
+                        recipients.with_mut(|r| r.push((String::new(), String::new())));
+                    },
+                    "+ Add recipient"
+                }
+
+                button {
+                    disabled: is_building(),
+                    onclick: move |_| {
+                        let Some(client) = global().api_client else {
+                            return;
+                        };
+                        spawn(async move {
+                            is_building.set(true);
+                            status.set("".to_string());
+
+                            if let Err(e) = async {
+                                let sender = Public::new_from_base36(source().trim()).ok_or(anyhow!("Invalid source address"))?;
+                                let mut receivers = vec![];
+                                for (r, a) in recipients() {
+                                    receivers.push((Public::new_from_base36(r.trim()).ok_or(anyhow!("Invalid receiver address"))?, to_nano(a.parse().map_err(|_| anyhow!("Invalid amount"))?)));
+                                }
+
+                                status.set("Building transaction...".to_string());
+                                let (transaction, input_amounts) = build_unsigned_transaction(&*client, sender, receivers, &ignore_inputs()).await?;
+                                let file = TransactionFile::new(TransactionFileKind::Unsigned, transaction, input_amounts);
+                                ignore_inputs.write().extend(file.transaction.inputs.iter().cloned());
+
+                                if save_transaction_file(&file, "Save Unsigned Transaction", &format!("unsigned-tx-{}.json", file.transaction.timestamp))? {
+                                    status.set("Unsigned transaction saved, move it to the offline wallet to sign it".to_string());
+                                } else {
+                                    status.set("".to_string());
+                                }
+
+                                Ok::<(), anyhow::Error>(())
+                            }
+                            .await
+                            {
+                                status.set(e.to_string());
+                            }
+
+                            is_building.set(false);
+                        });
+                    },
+                    if is_building() { "Building..." } else { "Build and save unsigned transaction" }
+                }
+            }
+
+            p { "{status}" }
+        }
+    }
+}
+
+/// Step 2, on the offline wallet holding the key
+#[component]
+fn SignOffline() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut loaded: Signal<Option<TransactionFile>> = use_signal(|| None);
+    let mut signer = use_signal(|| "".to_string());
+    let mut status = use_signal(|| "".to_string());
+
+    let mut wallet_names: Vec<String> = global().wallets.keys().cloned().collect();
+    wallet_names.sort();
+
+    rsx! {
+        div {
+            class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+            h3 { class: "font-semibold text-lg", "2. Sign (offline)" }
+
+            button {
+                onclick: move |_| {
+                    let Some(path) = pick_transaction_file("Open Unsigned Transaction") else {
+                        return;
+                    };
+                    match TransactionFile::load(&path) {
+                        Ok(file) if file.kind == TransactionFileKind::Unsigned => {
+                            // Preselect the vault wallet owning the inputs
+                            let owner = file.transaction.inputs.first().map(|i| i.output_owner);
+                            if let Some((name, _)) = global().wallets.iter().find(|(_, p)| Some(p.to_public()) == owner) {
+                                signer.set(name.clone());
+                            }
+                            loaded.set(Some(file));
+                            status.set("".to_string());
+                        }
+                        Ok(_) => status.set("This transaction is already signed".to_string()),
+                        Err(e) => status.set(format!("Could not load transaction: {e}")),
+                    }
+                },
+                "Load unsigned transaction"
+            }
+
+            if let Some(file) = loaded() {
+                TransactionReview { file: file.clone() }
+
+                label { class: "text-sm text-neutral-400", "Sign with" }
+                select {
+                    class: "bg-neutral-800 p-2 rounded w-full",
+                    value: "{signer}",
+                    onchange: move |e| signer.set(e.value()),
+                    option { value: "", disabled: true, "Vault wallet" }
+                    for name in wallet_names {
+                        option { value: "{name}", "{name}" }
+                    }
+                }
+
+                button {
+                    onclick: move |_| {
+                        let mut file = file.clone();
+                        spawn(async move {
+                            if let Err(e) = async {
+                                let private = *global().wallets.get(&signer()).ok_or(anyhow!("Choose a wallet to sign with"))?;
+                                if !ask_for_auth().await {
+                                    return Err(anyhow!("Unauthorized"));
+                                }
+
+                                if sign_transaction(&mut file.transaction, private)? == 0 {
+                                    return Err(anyhow!("The selected wallet does not own any input of this transaction"));
+                                }
+                                if verify_signatures(&file.transaction)?.iter().any(|s| *s != SignatureStatus::Valid) {
+                                    return Err(anyhow!("Some inputs are owned by another wallet, and are still unsigned"));
+                                }
+                                file.kind = TransactionFileKind::Signed;
+
+                                if save_transaction_file(&file, "Save Signed Transaction", &format!("signed-tx-{}.json", file.transaction.timestamp))? {
+                                    loaded.set(None);
+                                    status.set("Signed transaction saved, move it to the online wallet to broadcast it".to_string());
+                                }
+
+                                Ok::<(), anyhow::Error>(())
+                            }
+                            .await
+                            {
+                                status.set(e.to_string());
+                            }
+                        });
+                    },
+                    "Sign and save"
+                }
+            }
+
+            p { "{status}" }
+        }
+    }
+}
+
+/// Step 3, back on the online wallet
+#[component]
+fn BroadcastSigned() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut loaded: Signal<Option<TransactionFile>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_submitting = use_signal(|| false);
+
+    rsx! {
+        div {
+            class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+            h3 { class: "font-semibold text-lg", "3. Broadcast (online)" }
+
+            if global().api_client.is_none() {
+                p { class: "text-neutral-400", "Connect to a node to broadcast signed transactions." }
+            } else {
+                button {
+                    onclick: move |_| {
+                        let Some(path) = pick_transaction_file("Open Signed Transaction") else {
+                            return;
+                        };
+                        let result = TransactionFile::load(&path).and_then(|file| {
+                            if file.kind != TransactionFileKind::Signed {
+                                return Err(anyhow!("This transaction is not signed yet"));
+                            }
+                            if verify_signatures(&file.transaction)?.iter().any(|s| *s != SignatureStatus::Valid) {
+                                return Err(anyhow!("This transaction has missing or invalid signatures"));
+                            }
+                            Ok(file)
+                        });
+                        match result {
+                            Ok(file) => {
+                                loaded.set(Some(file));
+                                status.set("".to_string());
+                            }
+                            Err(e) => {
+                                loaded.set(None);
+                                status.set(format!("Could not load transaction: {e}"));
+                            }
+                        }
+                    },
+                    "Load signed transaction"
+                }
+
+                if let Some(file) = loaded() {
+                    TransactionReview { file: file.clone() }
+
+                    button {
+                        disabled: is_submitting(),
+                        onclick: move |_| {
+                            let Some(client) = global().api_client else {
+                                return;
+                            };
+                            // Computing PoW for it would only end in an unexplained refusal from the node
+                            if file.is_expired() {
+                                status.set(format!(
+                                    "This transaction expired {}, nodes no longer accept it. Build and sign it again",
+                                    format_timestamp_secs(file.expires_at())
+                                ));
+                                return;
+                            }
+                            let mut transaction = file.transaction.clone();
+                            spawn(async move {
+                                is_submitting.set(true);
+
+                                if let Err(e) = async {
+                                    status.set("Computing transaction PoW...".to_string());
+                                    transaction.compute_pow(&client.get_live_transaction_difficulty().await?, Some(0.2f64))?;
+                                    let transaction_id = transaction.transaction_id.ok_or(anyhow!("Transaction PoW failed"))?;
+                                    status.set("Submitting transaction...".to_string());
+                                    client.submit_transaction(transaction).await??;
+
+                                    loaded.set(None);
+                                    status.set(format!("Transaction submitted: {}", transaction_id.dump_base36()));
+
+                                    Ok::<(), anyhow::Error>(())
+                                }
+                                .await
+                                {
+                                    status.set(e.to_string());
+                                }
+
+                                is_submitting.set(false);
+                            });
+                        },
+                        if is_submitting() { "Submitting..." } else { "Compute PoW and submit" }
+                    }
+                }
+            }
+
+            p { "{status}" }
+        }
+    }
+}
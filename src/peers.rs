@@ -0,0 +1,354 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use dioxus::prelude::*;
+use snap_coin::full_node::connect_peer;
+use tokio::{net::lookup_host, time::sleep};
+
+use crate::{
+    connection_settings::{load_connection_settings, save_connection_settings, ConnectionSettings},
+    node_supervisor::PEER_CONNECT_TIMEOUT,
+    GlobalContext, NodeHandle,
+};
+
+/// How often the peer list is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A peer connected to the in-wallet node
+#[derive(Clone, PartialEq, Debug)]
+pub struct PeerInfo {
+    pub address: SocketAddr,
+    /// The peer connected to us, instead of us to it
+    pub inbound: bool,
+    /// None until the node measured the peer
+    pub latency: Option<Duration>,
+    pub height: Option<usize>,
+}
+
+pub async fn connected_peers(node: &NodeHandle) -> Vec<PeerInfo> {
+    let stats = node.supervisor.peer_stats();
+    let mut peers: Vec<PeerInfo> = node
+        .node_state
+        .connected_peers
+        .read()
+        .await
+        .values()
+        .map(|peer| PeerInfo {
+            address: peer.address,
+            inbound: peer.is_client,
+            latency: stats.get(&peer.address).map(|stats| stats.latency),
+            height: stats.get(&peer.address).map(|stats| stats.height),
+        })
+        .collect();
+    peers.sort_by_key(|peer| peer.address);
+    peers
+}
+
+/// Connect the node to a peer, on the node runtime so the connection ends with the node
+pub async fn connect(node: &NodeHandle, address: &str) -> Result<SocketAddr, anyhow::Error> {
+    let address = lookup_host(address)
+        .await?
+        .next()
+        .ok_or(anyhow!("Could not resolve {}", address))?;
+    if node.supervisor.denied_peers().contains(&address.ip()) {
+        return Err(anyhow!("{} is on the deny list", address.ip()));
+    }
+    if node
+        .node_state
+        .connected_peers
+        .read()
+        .await
+        .contains_key(&address)
+    {
+        return Err(anyhow!("Already connected to {}", address));
+    }
+
+    let blockchain = node.blockchain.clone();
+    let node_state = node.node_state.clone();
+    node.runtime
+        .spawn(async move {
+            tokio::time::timeout(
+                PEER_CONNECT_TIMEOUT,
+                connect_peer(address, &blockchain, &node_state),
+            )
+            .await
+            .map_err(|_| anyhow!("Timed out connecting to {}", address))?
+            .map_err(anyhow::Error::from)
+        })
+        .await??;
+    Ok(address)
+}
+
+/// Disconnect every connected peer whose address matches
+pub async fn disconnect(node: &NodeHandle, matches: impl Fn(&SocketAddr) -> bool) {
+    let peers: Vec<_> = node
+        .node_state
+        .connected_peers
+        .read()
+        .await
+        .values()
+        .filter(|peer| matches(&peer.address))
+        .cloned()
+        .collect();
+    for peer in peers {
+        let _ = peer.kill("Disconnected by the wallet".to_string()).await;
+    }
+}
+
+fn format_latency(latency: Option<Duration>) -> String {
+    latency.map_or("-".to_string(), |latency| {
+        format!("{} ms", latency.as_millis())
+    })
+}
+
+#[component]
+pub fn Peers() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut peers: Signal<Vec<PeerInfo>> = use_signal(Vec::new);
+    let mut status = use_signal(|| "".to_string());
+    let mut new_peer = use_signal(|| "".to_string());
+    let mut new_denied = use_signal(|| "".to_string());
+    let mut is_busy = use_signal(|| false);
+    let mut settings = use_signal(|| match load_connection_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            status.set(format!("Failed to load connection settings: {e}"));
+            ConnectionSettings::default()
+        }
+    });
+
+    use_future(move || async move {
+        loop {
+            let node = global.peek().internal_node.clone();
+            let current = match node {
+                Some(node) => connected_peers(&node).await,
+                None => vec![],
+            };
+            if *peers.peek() != current {
+                peers.set(current);
+            }
+            sleep(REFRESH_INTERVAL).await;
+        }
+    });
+
+    // Saved for the next start, and handed to the running node
+    let mut update_lists = move |update: &dyn Fn(&mut ConnectionSettings)| {
+        settings.with_mut(|settings| {
+            update(settings);
+            if let Err(e) = save_connection_settings(settings) {
+                status.set(format!("Failed to save peer lists: {e}"));
+            }
+            if let Some(node) = &global.peek().internal_node {
+                node.supervisor.set_peer_lists(
+                    settings.allowed_peers.clone(),
+                    settings.denied_peers.clone(),
+                );
+            }
+        });
+    };
+
+    let mut connect_to = move |address: String, allow: bool| {
+        let Some(node) = global().internal_node else {
+            status.set("The in-wallet node is not running".to_string());
+            return;
+        };
+        if allow {
+            update_lists(&|settings| {
+                if !settings.allowed_peers.contains(&address) {
+                    settings.allowed_peers.push(address.clone());
+                }
+            });
+        }
+        spawn(async move {
+            is_busy.set(true);
+            match connect(&node, &address).await {
+                Ok(address) => status.set(format!("Connected to {address}")),
+                Err(e) => status.set(e.to_string()),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let mut ban = move |ip: IpAddr| {
+        update_lists(&|settings| {
+            settings.denied_peers.insert(ip);
+        });
+        if let Some(node) = global().internal_node {
+            spawn(async move {
+                disconnect(&node, |address| address.ip() == ip).await;
+            });
+        }
+    };
+
+    if global().internal_node.is_none() {
+        return rsx! {
+            div {
+                class: "w-full h-full p-6 text-white flex flex-col gap-6",
+                h1 { class: "text-2xl font-bold text-nowrap", "Peers" }
+                p { class: "text-neutral-400", "Peers are managed by the in-wallet node, start it from the connection screen." }
+            }
+        };
+    }
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Peers" }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Connected ({peers().len()})" }
+
+                    div {
+                        class: "grid grid-cols-[1fr_6rem_6rem_6rem_max-content] items-center gap-x-4 text-xs text-neutral-500 px-4",
+                        span { "Address" }
+                        span { "Direction" }
+                        span { "Latency" }
+                        span { "Height" }
+                        span {}
+                    }
+                    div {
+                        class: "flex flex-col gap-2 overflow-auto pr-2",
+                        if peers().is_empty() {
+                            p { class: "text-neutral-400", "No peers connected" }
+                        }
+                        for peer in peers() {
+                            div {
+                                class: "bg-neutral-800 p-4 rounded grid grid-cols-[1fr_6rem_6rem_6rem_max-content] items-center gap-x-4 text-sm",
+                                span { class: "font-mono truncate", "{peer.address}" }
+                                span { if peer.inbound { "Inbound" } else { "Outbound" } }
+                                span { class: "font-mono", "{format_latency(peer.latency)}" }
+                                span { class: "font-mono", {peer.height.map_or("-".to_string(), |height| height.to_string())} }
+                                div {
+                                    class: "flex gap-2",
+                                    button {
+                                        class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                        title: "Keep this peer connected",
+                                        onclick: move |_| {
+                                            let address = peer.address.to_string();
+                                            update_lists(&|settings| {
+                                                if !settings.allowed_peers.contains(&address) {
+                                                    settings.allowed_peers.push(address.clone());
+                                                }
+                                            });
+                                        },
+                                        "Allow"
+                                    }
+                                    button {
+                                        class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                        onclick: move |_| {
+                                            if let Some(node) = global().internal_node {
+                                                spawn(async move {
+                                                    disconnect(&node, |address| *address == peer.address).await;
+                                                });
+                                            }
+                                        },
+                                        "Disconnect"
+                                    }
+                                    button {
+                                        class: "bg-transparent! border! border-red-400! text-red-400! text-xs",
+                                        onclick: move |_| ban(peer.address.ip()),
+                                        "Ban"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-auto",
+                    h3 { class: "font-semibold text-lg", "Connect" }
+                    input {
+                        r#type: "text",
+                        placeholder: "node.snap-coin.net:8998",
+                        value: "{new_peer}",
+                        oninput: move |e| new_peer.set(e.value()),
+                    }
+                    div {
+                        class: "flex gap-2",
+                        button {
+                            disabled: is_busy() || new_peer().trim().is_empty(),
+                            onclick: move |_| connect_to(new_peer().trim().to_string(), false),
+                            "Connect"
+                        }
+                        button {
+                            class: "bg-transparent! border! border-[var(--border)]!",
+                            disabled: is_busy() || new_peer().trim().is_empty(),
+                            onclick: move |_| connect_to(new_peer().trim().to_string(), true),
+                            "Connect and allow"
+                        }
+                    }
+                    p { "{status}" }
+
+                    h3 { class: "font-semibold text-lg", "Allow list" }
+                    p { class: "text-sm text-neutral-400", "Kept connected, and reconnected when they drop." }
+                    if settings().allowed_peers.is_empty() {
+                        p { class: "text-sm text-neutral-500", "No allowed peers" }
+                    }
+                    for address in settings().allowed_peers {
+                        div {
+                            class: "bg-neutral-800 p-2 rounded flex items-center justify-between gap-2 text-sm",
+                            span { class: "font-mono truncate", "{address}" }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                onclick: move |_| {
+                                    let address = address.clone();
+                                    update_lists(&|settings| settings.allowed_peers.retain(|allowed| *allowed != address));
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+
+                    h3 { class: "font-semibold text-lg", "Deny list" }
+                    p { class: "text-sm text-neutral-400", "Never connected, and dropped when they connect." }
+                    div {
+                        class: "flex gap-2",
+                        input {
+                            class: "flex-1 min-w-0",
+                            r#type: "text",
+                            placeholder: "203.0.113.7",
+                            value: "{new_denied}",
+                            oninput: move |e| new_denied.set(e.value()),
+                        }
+                        button {
+                            class: "bg-transparent! border! border-[var(--border)]!",
+                            onclick: move |_| match new_denied().trim().parse::<IpAddr>() {
+                                Ok(ip) => {
+                                    ban(ip);
+                                    new_denied.set("".to_string());
+                                }
+                                Err(_) => status.set(format!("{} is not an IP address", new_denied().trim())),
+                            },
+                            "Deny"
+                        }
+                    }
+                    if settings().denied_peers.is_empty() {
+                        p { class: "text-sm text-neutral-500", "No denied peers" }
+                    }
+                    for ip in settings().denied_peers {
+                        div {
+                            class: "bg-neutral-800 p-2 rounded flex items-center justify-between gap-2 text-sm",
+                            span { class: "font-mono truncate", "{ip}" }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                onclick: move |_| update_lists(&|settings| {
+                                    settings.denied_peers.remove(&ip);
+                                }),
+                                "Unban"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
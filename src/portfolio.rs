@@ -0,0 +1,243 @@
+use std::cmp::Reverse;
+
+use dioxus::prelude::*;
+use snap_coin::{
+    blockchain_data_provider::BlockchainDataProvider,
+    crypto::keys::Public,
+    to_snap,
+};
+
+use crate::{
+    chain::ChainError,
+    copy_box::CopyBox,
+    home::{fetch_history, format_timestamp_secs, HistoryTX},
+    sync::SyncState,
+    GlobalContext, Route,
+};
+
+const REFRESH: Asset = asset!("../assets/refresh.svg");
+
+/// How many of the latest transactions of each wallet are merged into the activity feed
+const ACTIVITY_PER_WALLET: usize = 10;
+
+#[derive(Clone)]
+struct WalletSummary {
+    name: String,
+    address: Public,
+    balance: u64,
+    utxo_count: usize,
+    last_activity: Option<u64>,
+}
+
+#[component]
+pub fn Portfolio() -> Element {
+    let mut global = consume_context::<Signal<GlobalContext>>();
+    if global().api_client.is_none() {
+        navigator().replace(Route::Connection);
+        return rsx! {};
+    }
+    let client = global().api_client.unwrap();
+
+    let mut summaries: Signal<Vec<WalletSummary>> = use_signal(Vec::new);
+    let mut activity: Signal<Vec<(String, HistoryTX)>> = use_signal(Vec::new);
+    let mut error = use_signal(|| "".to_string());
+    let mut loading = use_signal(|| false);
+    let mut refresh = use_signal(|| 0u32);
+    let sync = consume_context::<Signal<SyncState>>();
+
+    use_effect(move || {
+        refresh();
+        let _ = sync.read().revision;
+        let client = client.clone();
+        let vault = global().wallet_names();
+        let mut wallets: Vec<(String, Public)> = global()
+            .wallets
+            .iter()
+            .map(|(name, private)| (name.clone(), private.to_public()))
+            .collect();
+        wallets.sort_by(|a, b| a.0.cmp(&b.0));
+
+        spawn(async move {
+            loading.set(true);
+
+            let syncing = sync.peek().syncing;
+            let mut wallet_summaries = vec![];
+            let mut feed = vec![];
+            let mut errors = vec![];
+
+            for (name, public) in wallets {
+                let result = async {
+                    let balance = client.get_balance(public).await?;
+                    let utxo_count = client
+                        .get_available_transaction_outputs(public)
+                        .await?
+                        .len();
+                    let (history, history_errors) =
+                        fetch_history(&client, public, Some(ACTIVITY_PER_WALLET), &vault).await?;
+                    Ok::<_, ChainError>((balance, utxo_count, history, history_errors))
+                }
+                .await;
+
+                match result {
+                    Ok((balance, utxo_count, history, history_errors)) => {
+                        wallet_summaries.push(WalletSummary {
+                            name: name.clone(),
+                            address: public,
+                            balance,
+                            utxo_count,
+                            last_activity: history.iter().map(|tx| tx.timestamp).max(),
+                        });
+                        feed.extend(history.into_iter().map(|tx| (name.clone(), tx)));
+                        errors.extend(history_errors);
+                    }
+                    // Nothing else will load either
+                    Err(ChainError::ConnectionLost) => {
+                        errors.push(ChainError::ConnectionLost);
+                        break;
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+
+            wallet_summaries.sort_by_key(|summary| Reverse(summary.balance));
+            feed.sort_by_key(|(_, tx)| Reverse(tx.timestamp));
+
+            summaries.set(wallet_summaries);
+            activity.set(feed);
+            match errors.into_iter().next() {
+                Some(e) => error.set(format!(
+                    "Some data could not be loaded: {}",
+                    e.or_syncing(syncing)
+                )),
+                None => error.set("".into()),
+            }
+
+            loading.set(false);
+        });
+    });
+
+    let total: u64 = summaries.read().iter().map(|s| s.balance).sum();
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+
+            div {
+                class: "flex items-center justify-between",
+                h1 { class: "text-2xl font-bold text-nowrap", "Portfolio" }
+                img {
+                    src: REFRESH,
+                    class: "rounded-none! cursor-pointer invert".to_string() + if loading() { " spin-fast" } else { "" },
+                    onclick: move |_| refresh += 1
+                }
+            }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-1 flex flex-col gap-6",
+
+                    div {
+                        class: "bg-neutral-900 rounded-xl p-6 shadow",
+                        p { class: "text-sm text-neutral-400", "Total balance" }
+                        h2 { class: "text-3xl font-bold mt-2 font-mono", "{to_snap(total)} SNAP" }
+                        p { class: "text-sm text-neutral-400 mt-2", "Across {summaries.read().len()} wallets" }
+                    }
+
+                    div {
+                        class: "bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+                        h3 { class: "font-semibold text-lg", "Wallets" }
+
+                        for summary in summaries.read().iter() {
+                            {
+                                let share = if total > 0 { summary.balance as f64 / total as f64 * 100.0 } else { 0.0 };
+                                let last_activity = summary.last_activity.map(format_timestamp_secs).unwrap_or("No activity".into());
+                                let name = summary.name.clone();
+
+                                rsx! {
+                                    div {
+                                        key: "{summary.name}",
+                                        class: "bg-neutral-800 p-4 rounded flex flex-col gap-2 text-sm cursor-pointer",
+                                        onclick: move |_| {
+                                            global.write().selected_wallet = name.clone();
+                                            navigator().push(Route::Home);
+                                        },
+
+                                        div {
+                                            class: "flex items-center justify-between gap-5",
+                                            span { class: "font-bold truncate", "{summary.name}" }
+                                            span { class: "font-mono font-bold", "{to_snap(summary.balance):.4} SNAP" }
+                                        }
+                                        CopyBox { class: "w-full min-w-0", text: summary.address.dump_base36(), title: "Wallet Public Address" }
+                                        div {
+                                            class: "flex items-center justify-between gap-5 text-neutral-500 text-xs",
+                                            span { "{summary.utxo_count} UTXOs" }
+                                            span { "{last_activity}" }
+                                            span { class: "font-mono", "{share:.2}%" }
+                                        }
+                                        div {
+                                            class: "w-full h-1 bg-neutral-900 rounded",
+                                            div {
+                                                class: "h-1 rounded bg-[var(--accent)]",
+                                                style: "width: {share}%"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col overflow-hidden",
+                    h3 { class: "font-semibold text-lg mb-4", "Activity" }
+
+                    div {
+                        class: "flex flex-col gap-2 overflow-auto pr-2",
+
+                        for (wallet, tx) in activity.read().iter() {
+                            {
+                                let amount_class = if tx.internal_with.is_some() { "text-[var(--accent)]" } else if tx.is_send { "text-red-400" } else { "text-green-400" };
+                                let sign = if tx.is_send { "-" } else { "+" };
+                                let amount_text = format!("{sign}{:.4}", tx.amount_snap);
+                                let when = match &tx.internal_with {
+                                    Some(other) if tx.is_send => format!("Internal transfer to {other} · {}", tx.when),
+                                    Some(other) => format!("Internal transfer from {other} · {}", tx.when),
+                                    None => tx.when.clone(),
+                                };
+
+                                rsx! {
+                                    div {
+                                        class: "bg-neutral-800 p-4 rounded grid grid-cols-[max-content_1fr_max-content] items-center gap-x-4 text-sm",
+
+                                        span { class: "font-bold truncate", "{wallet}" }
+                                        CopyBox { class: "w-full min-w-0", text: tx.tx.dump_base36(), title: "Transaction ID" }
+                                        div {
+                                            class: "flex flex-col items-end",
+                                            span { class: "font-semibold font-bold {amount_class}", "{amount_text} SNAP" }
+                                            span { class: "text-neutral-500 text-xs whitespace-nowrap", "{when}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div {
+                    class: "p-10 flex items-center gap-5",
+                    p { class: "text-red-400! font-bold", "{error}" }
+                    button {
+                        disabled: loading(),
+                        onclick: move |_| refresh += 1,
+                        "Retry"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1070 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snap_coin::{
+    api::client::Client,
+    core::transaction::{Transaction, TransactionId},
+    crypto::{
+        keys::{Private, Public},
+        Hash, Signature,
+    },
+    to_snap,
+};
+
+use crate::{
+    chain::{input_amount, ChainError},
+    home::format_timestamp_secs,
+    receipt_document::extract_html_receipt,
+};
+
+/// Time format used by exported text receipts
+pub const RECEIPT_TIME_FORMAT: &str = "%A, %b %d %Y %I:%M %p %Z";
+
+pub fn format_receipt_time(timestamp: u64) -> String {
+    Local
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map_or(timestamp.to_string(), |time| {
+            time.format(RECEIPT_TIME_FORMAT).to_string()
+        })
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ReceiptFunder {
+    /// 1 based index of the input this funder describes
+    pub number: usize,
+    pub note: String,
+    pub address: String,
+    pub signature: String,
+    pub total: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ReceiptPayee {
+    /// 1 based index of the output this payee describes
+    pub number: usize,
+    pub note: String,
+    pub address: String,
+    pub total: String,
+}
+
+/// A legacy text receipt exported by `AnnotateTransaction`, split into its signed body and footer
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TextReceipt {
+    /// Everything the creator signature covers
+    pub body: String,
+    pub title_and_description: String,
+    pub transaction_id: String,
+    pub time: String,
+    pub height: String,
+    pub block_hash: String,
+    pub funders: Vec<ReceiptFunder>,
+    pub payees: Vec<ReceiptPayee>,
+    pub created_by: String,
+    pub creator_signature: String,
+}
+
+enum Section {
+    Network,
+    Funders,
+    Payees,
+}
+
+/// Parses the `#n: note` part of a funder or payee line
+fn numbered(rest: &str) -> Option<(usize, String)> {
+    let (number, note) = rest
+        .split_once(": ")
+        .unwrap_or((rest.trim_end_matches(':'), ""));
+    Some((number.parse().ok()?, note.to_string()))
+}
+
+impl TextReceipt {
+    pub fn parse(text: &str) -> Result<Self, anyhow::Error> {
+        let (body, footer) = text
+            .rsplit_once("Annotation Information\n")
+            .ok_or(anyhow!("Not a receipt, the annotation footer is missing"))?;
+
+        let mut receipt = TextReceipt {
+            body: body.to_string(),
+            ..Default::default()
+        };
+
+        for line in footer.lines() {
+            if let Some(created_by) = line.strip_prefix("Created by: ") {
+                receipt.created_by = created_by.trim().to_string();
+            } else if let Some(signature) = line.strip_prefix("Creator signature: ") {
+                receipt.creator_signature = signature.trim().to_string();
+            }
+        }
+
+        // The description is free text, everything after the network header is one field per line
+        let header = body
+            .strip_prefix("Transaction Confirmation\n\n")
+            .ok_or(anyhow!("Not a receipt, the header is missing"))?;
+        let (title_and_description, structured) =
+            header
+                .rsplit_once("\n\nNetwork Information\n")
+                .ok_or(anyhow!("Not a receipt, the network information is missing"))?;
+        receipt.title_and_description = title_and_description.to_string();
+
+        let mut section = Section::Network;
+        for line in structured.lines() {
+            match line {
+                "" => continue,
+                "Funders" => {
+                    section = Section::Funders;
+                    continue;
+                }
+                "Payees" => {
+                    section = Section::Payees;
+                    continue;
+                }
+                _ => {}
+            }
+
+            match section {
+                Section::Network => {
+                    if let Some(transaction_id) = line.strip_prefix("Transaction ID: ") {
+                        receipt.transaction_id = transaction_id.to_string();
+                    } else if let Some(time) = line.strip_prefix("Time: ") {
+                        receipt.time = time.to_string();
+                    } else if let Some(block) = line.strip_prefix("Included in block: #") {
+                        let (height, hash) = block
+                            .split_once(": ")
+                            .ok_or(anyhow!("Malformed block line"))?;
+                        receipt.height = height.to_string();
+                        receipt.block_hash = hash.to_string();
+                    }
+                }
+                Section::Funders => {
+                    if let Some(rest) = line.strip_prefix("Funder #") {
+                        let (number, note) =
+                            numbered(rest).ok_or(anyhow!("Malformed funder line"))?;
+                        receipt.funders.push(ReceiptFunder {
+                            number,
+                            note,
+                            ..Default::default()
+                        });
+                    } else if let Some(funder) = receipt.funders.last_mut() {
+                        if let Some(address) = line.strip_prefix("Sender address: ") {
+                            funder.address = address.to_string();
+                        } else if let Some(signature) = line.strip_prefix("Signature: ") {
+                            funder.signature = signature.to_string();
+                        } else if let Some(total) = line.strip_prefix("Total: ") {
+                            funder.total = total.to_string();
+                        }
+                    }
+                }
+                Section::Payees => {
+                    if let Some(rest) = line.strip_prefix("Payee #") {
+                        let (number, note) =
+                            numbered(rest).ok_or(anyhow!("Malformed payee line"))?;
+                        receipt.payees.push(ReceiptPayee {
+                            number,
+                            note,
+                            ..Default::default()
+                        });
+                    } else if let Some(payee) = receipt.payees.last_mut() {
+                        if let Some(address) = line.strip_prefix("Payee address: ") {
+                            payee.address = address.to_string();
+                        } else if let Some(total) = line.strip_prefix("Total: ") {
+                            payee.total = total.to_string();
+                        }
+                    }
+                }
+            }
+        }
+
+        if receipt.transaction_id.is_empty() {
+            return Err(anyhow!("Not a receipt, the transaction id is missing"));
+        }
+
+        Ok(receipt)
+    }
+
+    /// Whether the creator signature covers the receipt body
+    pub fn signature_valid(&self) -> bool {
+        let (Some(creator), Some(signature)) = (
+            Public::new_from_base36(&self.created_by),
+            Signature::new_from_base36(&self.creator_signature),
+        ) else {
+            return false;
+        };
+        signature
+            .validate_with_public(&creator, self.body.as_bytes())
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CheckStatus {
+    /// Matches the signature or the chain
+    Authentic,
+    /// Differs from what was signed or what is on chain
+    Altered,
+    /// Not found on chain
+    Absent,
+    /// Left out of the receipt by its creator
+    Hidden,
+    /// Could not be checked, for example without a node connection
+    Unchecked,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Check {
+    pub part: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn new(part: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Check {
+            part: part.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn compare(part: impl Into<String>, listed: &str, on_chain: &str) -> Self {
+        if listed == on_chain {
+            Check::new(part, CheckStatus::Authentic, listed)
+        } else {
+            Check::new(
+                part,
+                CheckStatus::Altered,
+                format!("Listed as {listed}, chain says {on_chain}"),
+            )
+        }
+    }
+}
+
+/// Cross check every part of a receipt against the chain, the signature is checked separately
+pub async fn verify_text_receipt(
+    receipt: &TextReceipt,
+    client: Option<&Client>,
+) -> Result<Vec<Check>, ChainError> {
+    let mut checks = vec![];
+
+    let Some(client) = client else {
+        checks.push(Check::new(
+            "Transaction",
+            CheckStatus::Unchecked,
+            "Connect to a node to check the receipt against the chain",
+        ));
+        return Ok(checks);
+    };
+
+    let info = match TransactionId::new_from_base36(&receipt.transaction_id) {
+        Some(transaction_id) => client.get_transaction_and_info(&transaction_id).await?,
+        None => None,
+    };
+    let Some(info) = info else {
+        checks.push(Check::new(
+            "Transaction",
+            CheckStatus::Absent,
+            format!("{} is not on chain", receipt.transaction_id),
+        ));
+        return Ok(checks);
+    };
+    checks.push(Check::new(
+        "Transaction",
+        CheckStatus::Authentic,
+        &receipt.transaction_id,
+    ));
+    checks.push(Check::compare(
+        "Block hash",
+        &receipt.block_hash,
+        &info.in_block.dump_base36(),
+    ));
+    checks.push(Check::compare(
+        "Height",
+        &receipt.height,
+        &info.at_height.to_string(),
+    ));
+
+    // The receipt is written in the local time zone of its creator, and only to the minute
+    let chain_minute = info.transaction.timestamp / 60 * 60;
+    checks.push(
+        match DateTime::parse_from_str(&receipt.time, &RECEIPT_TIME_FORMAT.replace("%Z", "%:z")) {
+            Ok(time) if time.timestamp() as u64 == chain_minute => {
+                Check::new("Time", CheckStatus::Authentic, &receipt.time)
+            }
+            Ok(_) => Check::new(
+                "Time",
+                CheckStatus::Altered,
+                format!("{} does not match the transaction time", receipt.time),
+            ),
+            Err(_) => Check::new(
+                "Time",
+                CheckStatus::Unchecked,
+                format!("Could not read {}", receipt.time),
+            ),
+        },
+    );
+
+    for (i, input) in info.transaction.inputs.iter().enumerate() {
+        let part = format!("Funder #{}", i + 1);
+        let Some(funder) = receipt.funders.iter().find(|f| f.number == i + 1) else {
+            checks.push(Check::new(part, CheckStatus::Hidden, "Not listed"));
+            continue;
+        };
+
+        checks.push(Check::compare(
+            format!("{part} address"),
+            &funder.address,
+            &input.output_owner.dump_base36(),
+        ));
+        checks.push(Check::compare(
+            format!("{part} signature"),
+            &funder.signature,
+            &input
+                .signature
+                .map_or("None".to_string(), |s| s.dump_base36()),
+        ));
+        if funder.total == "Unknown" {
+            checks.push(Check::new(
+                format!("{part} amount"),
+                CheckStatus::Unchecked,
+                "The receipt does not list an amount",
+            ));
+            continue;
+        }
+        checks.push(match input_amount(client, input).await {
+            Ok(amount) => Check::compare(
+                format!("{part} amount"),
+                &funder.total,
+                &format!("-{} SNAP", to_snap(amount)),
+            ),
+            Err(ChainError::ConnectionLost) => return Err(ChainError::ConnectionLost),
+            Err(e) => Check::new(
+                format!("{part} amount"),
+                CheckStatus::Unchecked,
+                e.to_string(),
+            ),
+        });
+    }
+
+    for (i, output) in info.transaction.outputs.iter().enumerate() {
+        let part = format!("Payee #{}", i + 1);
+        let Some(payee) = receipt.payees.iter().find(|p| p.number == i + 1) else {
+            checks.push(Check::new(part, CheckStatus::Hidden, "Not listed"));
+            continue;
+        };
+
+        checks.push(Check::compare(
+            format!("{part} address"),
+            &payee.address,
+            &output.receiver.dump_base36(),
+        ));
+        checks.push(Check::compare(
+            format!("{part} amount"),
+            &payee.total,
+            &format!("+{} SNAP", to_snap(output.amount)),
+        ));
+    }
+
+    // Entries for inputs or outputs the transaction does not have
+    for funder in receipt
+        .funders
+        .iter()
+        .filter(|f| f.number == 0 || f.number > info.transaction.inputs.len())
+    {
+        checks.push(Check::new(
+            format!("Funder #{}", funder.number),
+            CheckStatus::Absent,
+            "The transaction has no such input",
+        ));
+    }
+    for payee in receipt
+        .payees
+        .iter()
+        .filter(|p| p.number == 0 || p.number > info.transaction.outputs.len())
+    {
+        checks.push(Check::new(
+            format!("Payee #{}", payee.number),
+            CheckStatus::Absent,
+            "The transaction has no such output",
+        ));
+    }
+
+    Ok(checks)
+}
+
+/// Version of the structured receipt format, bumped on incompatible changes
+pub const RECEIPT_VERSION: u32 = 1;
+
+/// Prefixed to the canonical payload before signing, so a receipt signature can never double as another signature
+const RECEIPT_SIGNING_PREFIX: &[u8] = b"Snap Coin Receipt:\n";
+
+/// Prefixed to a disclosed entry before it is hashed into a commitment
+const COMMITMENT_PREFIX: &[u8] = b"Snap Coin Receipt Entry:\n";
+
+/// What a receipt says about one funder or payee
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EntryDetails {
+    pub address: String,
+    /// Amount in nano, None when the funding transaction could not be found
+    pub amount: Option<u64>,
+    pub note: String,
+    /// Input signature, funders only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl EntryDetails {
+    /// Salted sha256 over the details, binding the receipt to them without revealing them
+    pub fn commitment(&self, salt: &Hash) -> Result<Hash, anyhow::Error> {
+        let mut hasher = Sha256::new();
+        hasher.update(COMMITMENT_PREFIX);
+        hasher.update(salt.dump_buf());
+        hasher.update(serde_json::to_vec(self)?);
+        Ok(Hash::new_from_buf(hasher.finalize().into()))
+    }
+}
+
+/// Funder or payee of a structured receipt
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ReceiptEntry {
+    Shown(EntryDetails),
+    /// Left out by the creator, only its commitment is signed
+    Hidden {
+        commitment: Hash,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryRole {
+    Funder,
+    Payee,
+}
+
+impl EntryRole {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryRole::Funder => "funder",
+            EntryRole::Payee => "payee",
+        }
+    }
+
+    /// Roles in words, like "funder and payee"
+    pub fn describe(roles: &[EntryRole]) -> String {
+        roles
+            .iter()
+            .map(|role| role.label())
+            .collect::<Vec<_>>()
+            .join(" and ")
+    }
+
+    /// Roles an address plays in a transaction
+    pub fn of(address: &Public, transaction: &Transaction) -> Vec<EntryRole> {
+        let mut roles = vec![];
+        if transaction
+            .inputs
+            .iter()
+            .any(|input| input.output_owner == *address)
+        {
+            roles.push(EntryRole::Funder);
+        }
+        if transaction
+            .outputs
+            .iter()
+            .any(|output| output.receiver == *address)
+        {
+            roles.push(EntryRole::Payee);
+        }
+        roles
+    }
+}
+
+/// Opens one hidden entry of a receipt, handed out separately from the receipt
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EntryReveal {
+    pub transaction_id: String,
+    pub role: EntryRole,
+    /// 0 based index of the input or output
+    pub index: usize,
+    pub salt: Hash,
+    pub details: EntryDetails,
+}
+
+impl EntryReveal {
+    pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
+        Ok(serde_json::from_str(json.trim())?)
+    }
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Whether these details are the ones behind a commitment
+    pub fn opens(&self, commitment: &Hash) -> bool {
+        self.details
+            .commitment(&self.salt)
+            .is_ok_and(|computed| computed == *commitment)
+    }
+}
+
+/// Everything a structured receipt signs
+/// Serialized as compact JSON in field order, which makes the signed bytes deterministic
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ReceiptPayload {
+    pub version: u32,
+    pub title: String,
+    pub description: String,
+    pub transaction_id: String,
+    pub block_hash: String,
+    pub height: u64,
+    pub timestamp: u64,
+    /// One entry for every transaction input, in order
+    pub funders: Vec<ReceiptEntry>,
+    /// One entry for every transaction output, in order
+    pub payees: Vec<ReceiptEntry>,
+    pub creator: String,
+    /// How the creator took part in the transaction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub creator_roles: Vec<EntryRole>,
+}
+
+impl ReceiptPayload {
+    pub fn creator_role_text(&self) -> String {
+        EntryRole::describe(&self.creator_roles)
+    }
+
+    fn signing_buf(&self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok([RECEIPT_SIGNING_PREFIX, &serde_json::to_vec(self)?].concat())
+    }
+
+    pub fn sign(self, mut private: Private) -> Result<SignedReceipt, anyhow::Error> {
+        let signature = Signature::new_signature(&mut private, &self.signing_buf()?);
+        Ok(SignedReceipt {
+            payload: self,
+            signature: signature.dump_base36(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SignedReceipt {
+    pub payload: ReceiptPayload,
+    pub signature: String,
+}
+
+impl SignedReceipt {
+    pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
+        let receipt: SignedReceipt = serde_json::from_str(json)?;
+        if receipt.payload.version != RECEIPT_VERSION {
+            return Err(anyhow!(
+                "Unsupported receipt version {}",
+                receipt.payload.version
+            ));
+        }
+        Ok(receipt)
+    }
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Whether the signature covers the payload and was made by the listed creator
+    pub fn signature_valid(&self) -> bool {
+        let (Some(creator), Some(signature), Ok(signing_buf)) = (
+            Public::new_from_base36(&self.payload.creator),
+            Signature::new_from_base36(&self.signature),
+            self.payload.signing_buf(),
+        ) else {
+            return false;
+        };
+        signature
+            .validate_with_public(&creator, &signing_buf)
+            .unwrap_or(false)
+    }
+
+    /// Human readable receipt, ending with the signed receipt itself so it can be verified
+    pub fn render_text(&self) -> Result<String, anyhow::Error> {
+        let payload = &self.payload;
+        let time = format_receipt_time(payload.timestamp);
+
+        let mut text = format!(
+            "Transaction Confirmation\n\n{}\n{}\n\nNetwork Information\nTransaction ID: {}\nTime: {}\nIncluded in block: #{}: {}\n\nFunders\n",
+            payload.title, payload.description, payload.transaction_id, time, payload.height, payload.block_hash
+        );
+        for (i, funder) in payload.funders.iter().enumerate() {
+            match funder {
+                ReceiptEntry::Shown(details) => {
+                    text += &format!(
+                        "Funder #{}: {}\nSender address: {}\nSignature: {}\nTotal: {}\n\n",
+                        i + 1,
+                        details.note,
+                        details.address,
+                        details.signature.as_deref().unwrap_or("None"),
+                        details
+                            .amount
+                            .map_or("Unknown".to_string(), |a| format!("-{} SNAP", to_snap(a)))
+                    );
+                }
+                ReceiptEntry::Hidden { commitment } => {
+                    text += &format!(
+                        "Funder #{}: hidden\nCommitment: {}\n\n",
+                        i + 1,
+                        commitment.dump_base36()
+                    )
+                }
+            }
+        }
+
+        text += "Payees\n";
+        for (i, payee) in payload.payees.iter().enumerate() {
+            match payee {
+                ReceiptEntry::Shown(details) => {
+                    text += &format!(
+                        "Payee #{}: {}\nPayee address: {}\nTotal: {}\n\n",
+                        i + 1,
+                        details.note,
+                        details.address,
+                        details
+                            .amount
+                            .map_or("Unknown".to_string(), |a| format!("+{} SNAP", to_snap(a)))
+                    );
+                }
+                ReceiptEntry::Hidden { commitment } => {
+                    text += &format!(
+                        "Payee #{}: hidden\nCommitment: {}\n\n",
+                        i + 1,
+                        commitment.dump_base36()
+                    )
+                }
+            }
+        }
+
+        text += &format!("Annotation Information\nCreated by: {}\n", payload.creator);
+        if !payload.creator_roles.is_empty() {
+            text += &format!("Creator role: {}\n", payload.creator_role_text());
+        }
+        text += &format!(
+            "Creator signature: {}\n{RECEIPT_DATA_PREFIX}{}",
+            self.signature,
+            serde_json::to_string(self)?
+        );
+        Ok(text)
+    }
+}
+
+/// Line of a text receipt that carries the signed structured receipt
+const RECEIPT_DATA_PREFIX: &str = "Receipt data: ";
+
+/// Any receipt the wallet can read
+#[derive(Clone, PartialEq, Debug)]
+pub enum Receipt {
+    Structured(SignedReceipt),
+    /// Text receipt exported before receipts were structured
+    Legacy(TextReceipt),
+}
+
+impl Receipt {
+    /// Accepts a JSON or HTML receipt, a text receipt carrying receipt data, or a legacy text receipt
+    pub fn decode(text: &str) -> Result<Self, anyhow::Error> {
+        if let Ok(receipt) = SignedReceipt::from_json(text.trim()) {
+            return Ok(Receipt::Structured(receipt));
+        }
+        if let Some(data) = extract_html_receipt(text) {
+            return Ok(Receipt::Structured(SignedReceipt::from_json(data)?));
+        }
+        if let Some(data) = text
+            .lines()
+            .find_map(|line| line.strip_prefix(RECEIPT_DATA_PREFIX))
+        {
+            let receipt = SignedReceipt::from_json(data)?;
+            // Only the receipt data is signed, so the readable part has to be exactly what it renders to
+            if receipt.render_text()? != text.replace("\r\n", "\n").trim_end() {
+                return Err(anyhow!(
+                    "The readable part of the receipt was changed, it no longer matches the signed receipt data"
+                ));
+            }
+            return Ok(Receipt::Structured(receipt));
+        }
+        Ok(Receipt::Legacy(TextReceipt::parse(text)?))
+    }
+}
+
+/// Details of an entry that can be checked against the chain, shown or opened by a reveal
+fn disclosed<'a>(
+    payload: &ReceiptPayload,
+    entry: &'a ReceiptEntry,
+    role: EntryRole,
+    index: usize,
+    reveals: &'a [EntryReveal],
+    checks: &mut Vec<Check>,
+) -> Option<(&'a EntryDetails, String)> {
+    let part = match role {
+        EntryRole::Funder => format!("Funder #{}", index + 1),
+        EntryRole::Payee => format!("Payee #{}", index + 1),
+    };
+    let commitment = match entry {
+        ReceiptEntry::Shown(details) => return Some((details, part)),
+        ReceiptEntry::Hidden { commitment } => commitment,
+    };
+
+    let Some(reveal) = reveals.iter().find(|reveal| {
+        reveal.transaction_id == payload.transaction_id
+            && reveal.role == role
+            && reveal.index == index
+    }) else {
+        checks.push(Check::new(
+            part,
+            CheckStatus::Hidden,
+            format!("Not disclosed, committed as {}", commitment.dump_base36()),
+        ));
+        return None;
+    };
+    if !reveal.opens(commitment) {
+        checks.push(Check::new(
+            part,
+            CheckStatus::Altered,
+            "The reveal does not match the signed commitment",
+        ));
+        return None;
+    }
+    checks.push(Check::new(
+        &part,
+        CheckStatus::Authentic,
+        "Revealed, matches the signed commitment",
+    ));
+    Some((&reveal.details, format!("{part} (revealed)")))
+}
+
+/// Cross check a structured receipt and any reveals of its hidden entries against the chain, the signature is checked separately
+pub async fn verify_structured_receipt(
+    payload: &ReceiptPayload,
+    reveals: &[EntryReveal],
+    client: Option<&Client>,
+) -> Result<Vec<Check>, ChainError> {
+    let mut checks = vec![];
+
+    let Some(client) = client else {
+        checks.push(Check::new(
+            "Transaction",
+            CheckStatus::Unchecked,
+            "Connect to a node to check the receipt against the chain",
+        ));
+        return Ok(checks);
+    };
+
+    let info = match TransactionId::new_from_base36(&payload.transaction_id) {
+        Some(transaction_id) => client.get_transaction_and_info(&transaction_id).await?,
+        None => None,
+    };
+    let Some(info) = info else {
+        checks.push(Check::new(
+            "Transaction",
+            CheckStatus::Absent,
+            format!("{} is not on chain", payload.transaction_id),
+        ));
+        return Ok(checks);
+    };
+    checks.push(Check::new(
+        "Transaction",
+        CheckStatus::Authentic,
+        &payload.transaction_id,
+    ));
+    checks.push(Check::compare(
+        "Block hash",
+        &payload.block_hash,
+        &info.in_block.dump_base36(),
+    ));
+    checks.push(Check::compare(
+        "Height",
+        &payload.height.to_string(),
+        &info.at_height.to_string(),
+    ));
+    checks.push(Check::compare(
+        "Time",
+        &format_timestamp_secs(payload.timestamp),
+        &format_timestamp_secs(info.transaction.timestamp),
+    ));
+
+    // The creator has to have taken part in the transaction the way the receipt claims
+    if let Some(creator) = Public::new_from_base36(&payload.creator) {
+        let on_chain = EntryRole::of(&creator, &info.transaction);
+        for role in &payload.creator_roles {
+            checks.push(if on_chain.contains(role) {
+                Check::new(
+                    format!("Creator as {}", role.label()),
+                    CheckStatus::Authentic,
+                    &payload.creator,
+                )
+            } else {
+                Check::new(
+                    format!("Creator as {}", role.label()),
+                    CheckStatus::Altered,
+                    format!(
+                        "{} is not a {} of this transaction",
+                        payload.creator,
+                        role.label()
+                    ),
+                )
+            });
+        }
+    }
+
+    // Every input and output has an entry, so a shorter list means entries were dropped
+    if payload.funders.len() != info.transaction.inputs.len() {
+        checks.push(Check::new(
+            "Funders",
+            CheckStatus::Altered,
+            format!(
+                "Receipt lists {} funders, the transaction has {} inputs",
+                payload.funders.len(),
+                info.transaction.inputs.len()
+            ),
+        ));
+    }
+    if payload.payees.len() != info.transaction.outputs.len() {
+        checks.push(Check::new(
+            "Payees",
+            CheckStatus::Altered,
+            format!(
+                "Receipt lists {} payees, the transaction has {} outputs",
+                payload.payees.len(),
+                info.transaction.outputs.len()
+            ),
+        ));
+    }
+
+    for (i, (funder, input)) in payload
+        .funders
+        .iter()
+        .zip(info.transaction.inputs.iter())
+        .enumerate()
+    {
+        let Some((details, part)) =
+            disclosed(payload, funder, EntryRole::Funder, i, reveals, &mut checks)
+        else {
+            continue;
+        };
+
+        checks.push(Check::compare(
+            format!("{part} address"),
+            &details.address,
+            &input.output_owner.dump_base36(),
+        ));
+        if let Some(signature) = &details.signature {
+            checks.push(Check::compare(
+                format!("{part} signature"),
+                signature,
+                &input
+                    .signature
+                    .map_or("None".to_string(), |s| s.dump_base36()),
+            ));
+        }
+        let Some(amount) = details.amount else {
+            checks.push(Check::new(
+                format!("{part} amount"),
+                CheckStatus::Unchecked,
+                "The receipt does not list an amount",
+            ));
+            continue;
+        };
+        checks.push(match input_amount(client, input).await {
+            Ok(on_chain) => Check::compare(
+                format!("{part} amount"),
+                &format!("{} SNAP", to_snap(amount)),
+                &format!("{} SNAP", to_snap(on_chain)),
+            ),
+            Err(ChainError::ConnectionLost) => return Err(ChainError::ConnectionLost),
+            Err(e) => Check::new(
+                format!("{part} amount"),
+                CheckStatus::Unchecked,
+                e.to_string(),
+            ),
+        });
+    }
+
+    for (i, (payee, output)) in payload
+        .payees
+        .iter()
+        .zip(info.transaction.outputs.iter())
+        .enumerate()
+    {
+        let Some((details, part)) =
+            disclosed(payload, payee, EntryRole::Payee, i, reveals, &mut checks)
+        else {
+            continue;
+        };
+
+        checks.push(Check::compare(
+            format!("{part} address"),
+            &details.address,
+            &output.receiver.dump_base36(),
+        ));
+        checks.push(Check::compare(
+            format!("{part} amount"),
+            &details
+                .amount
+                .map_or("Unknown".to_string(), |a| format!("{} SNAP", to_snap(a))),
+            &format!("{} SNAP", to_snap(output.amount)),
+        ));
+    }
+
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(note: &str, amount: u64) -> EntryDetails {
+        EntryDetails {
+            address: Private::new_random().to_public().dump_base36(),
+            amount: Some(amount),
+            note: note.to_string(),
+            signature: None,
+        }
+    }
+
+    fn signed_receipt() -> (SignedReceipt, Private) {
+        let private = Private::new_random();
+        let payload = ReceiptPayload {
+            version: RECEIPT_VERSION,
+            title: "Rent".to_string(),
+            description: "October".to_string(),
+            transaction_id: Hash::new_from_buf([1; 32]).dump_base36(),
+            block_hash: Hash::new_from_buf([2; 32]).dump_base36(),
+            height: 42,
+            timestamp: 1_760_000_000,
+            funders: vec![ReceiptEntry::Shown(details("From savings", 1_500))],
+            payees: vec![
+                ReceiptEntry::Shown(details("Landlord", 1_000)),
+                ReceiptEntry::Hidden {
+                    commitment: Hash::new_from_buf([3; 32]),
+                },
+            ],
+            creator: private.to_public().dump_base36(),
+            creator_roles: vec![EntryRole::Funder],
+        };
+        (payload.sign(private).unwrap(), private)
+    }
+
+    #[test]
+    fn json_round_trip_keeps_signature_valid() {
+        let (receipt, _) = signed_receipt();
+        assert!(receipt.signature_valid());
+
+        let decoded = SignedReceipt::from_json(&receipt.to_json().unwrap()).unwrap();
+        assert_eq!(decoded, receipt);
+        assert!(decoded.signature_valid());
+    }
+
+    #[test]
+    fn text_receipt_decodes_to_structured() {
+        let (receipt, _) = signed_receipt();
+        let text = receipt.render_text().unwrap();
+        assert_eq!(
+            Receipt::decode(&text).unwrap(),
+            Receipt::Structured(receipt.clone())
+        );
+        assert_eq!(
+            Receipt::decode(&text.replace('\n', "\r\n")).unwrap(),
+            Receipt::Structured(receipt)
+        );
+    }
+
+    #[test]
+    fn changed_readable_part_is_rejected() {
+        let (receipt, _) = signed_receipt();
+        let text = receipt
+            .render_text()
+            .unwrap()
+            .replace("Payee #1: Landlord", "Payee #1: Someone else");
+        assert!(Receipt::decode(&text).is_err());
+    }
+
+    #[test]
+    fn changed_payload_fails_signature() {
+        let (receipt, _) = signed_receipt();
+
+        let mut changed = receipt.clone();
+        changed.payload.height += 1;
+        assert!(!changed.signature_valid());
+
+        let mut changed = receipt.clone();
+        changed.payload.payees.swap(0, 1);
+        assert!(!changed.signature_valid());
+
+        // Signed by someone other than the listed creator
+        let mut changed = receipt;
+        changed.payload.creator = Private::new_random().to_public().dump_base36();
+        assert!(!changed.signature_valid());
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        let (receipt, private) = signed_receipt();
+        let mut payload = receipt.payload;
+        payload.version = RECEIPT_VERSION + 1;
+        let json = payload.sign(private).unwrap().to_json().unwrap();
+        assert!(SignedReceipt::from_json(&json).is_err());
+    }
+
+    fn reveal(details: EntryDetails) -> (EntryReveal, Hash) {
+        let salt = Hash::new_from_buf(rand::random());
+        let commitment = details.commitment(&salt).unwrap();
+        let reveal = EntryReveal {
+            transaction_id: Hash::new_from_buf([1; 32]).dump_base36(),
+            role: EntryRole::Payee,
+            index: 1,
+            salt,
+            details,
+        };
+        (reveal, commitment)
+    }
+
+    #[test]
+    fn reveal_opens_its_commitment() {
+        let (reveal, commitment) = reveal(details("Landlord", 1_000));
+        assert!(reveal.opens(&commitment));
+
+        let decoded = EntryReveal::from_json(&reveal.to_json().unwrap()).unwrap();
+        assert_eq!(decoded, reveal);
+        assert!(decoded.opens(&commitment));
+    }
+
+    #[test]
+    fn changed_reveal_does_not_open() {
+        let (reveal, commitment) = reveal(details("Landlord", 1_000));
+
+        let mut changed = reveal.clone();
+        changed.salt = Hash::new_from_buf(rand::random());
+        assert!(!changed.opens(&commitment));
+
+        let mut changed = reveal.clone();
+        changed.details.amount = Some(2_000);
+        assert!(!changed.opens(&commitment));
+
+        let mut changed = reveal.clone();
+        changed.details.note = "Someone else".to_string();
+        assert!(!changed.opens(&commitment));
+
+        let mut changed = reveal;
+        changed.details.address = Private::new_random().to_public().dump_base36();
+        assert!(!changed.opens(&commitment));
+    }
+
+    #[test]
+    fn same_details_commit_differently_per_salt() {
+        let details = details("Landlord", 1_000);
+        let first = details.commitment(&Hash::new_from_buf([4; 32])).unwrap();
+        let second = details.commitment(&Hash::new_from_buf([5; 32])).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            details.commitment(&Hash::new_from_buf([4; 32])).unwrap()
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use qrcode::{render::svg, Color, QrCode};
+use snap_coin::to_snap;
+
+use crate::receipt::{format_receipt_time, ReceiptEntry, SignedReceipt};
+
+/// Public explorer page of a transaction, where the receipt QR code points
+const EXPLORER_TRANSACTION_URL: &str = "https://explorer.snap-coin.net/tx/";
+
+/// Opening tag of the element HTML receipts carry their signed receipt in
+const HTML_RECEIPT_TAG: &str = r#"<script type="application/json" id="snap-coin-receipt">"#;
+
+/// Name of the file PDF receipts carry their signed receipt in
+const PDF_RECEIPT_FILE: &str = "receipt.json";
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const QR_SIZE: f32 = 110.0;
+const BRAND_RGB: (f32, f32, f32) = (0.31, 0.27, 0.9);
+const TEXT_RGB: (f32, f32, f32) = (0.09, 0.09, 0.09);
+const MUTED_RGB: (f32, f32, f32) = (0.45, 0.45, 0.45);
+
+pub fn transaction_url(transaction_id: &str) -> String {
+    format!("{EXPLORER_TRANSACTION_URL}{transaction_id}")
+}
+
+/// A funder or payee the way documents show it
+enum Row {
+    Shown {
+        number: usize,
+        note: String,
+        address: String,
+        amount: String,
+    },
+    Hidden {
+        number: usize,
+        commitment: String,
+    },
+}
+
+fn rows(entries: &[ReceiptEntry], sign: &str) -> Vec<Row> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| match entry {
+            ReceiptEntry::Shown(details) => Row::Shown {
+                number: i + 1,
+                note: details.note.clone(),
+                address: details.address.clone(),
+                amount: details.amount.map_or("Unknown".to_string(), |a| {
+                    format!("{sign}{} SNAP", to_snap(a))
+                }),
+            },
+            ReceiptEntry::Hidden { commitment } => Row::Hidden {
+                number: i + 1,
+                commitment: commitment.dump_base36(),
+            },
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn html_rows(label: &str, rows: &[Row]) -> String {
+    rows.iter()
+        .map(|row| match row {
+            Row::Shown {
+                number,
+                note,
+                address,
+                amount,
+            } => format!(
+                r#"<tr><td>{label} #{number}</td><td>{}</td><td class="mono">{}</td><td class="amount">{}</td></tr>"#,
+                escape_html(note),
+                escape_html(address),
+                escape_html(amount)
+            ),
+            Row::Hidden { number, commitment } => format!(
+                r#"<tr class="hidden"><td>{label} #{number}</td><td>Hidden</td><td class="mono">Commitment {commitment}</td><td></td></tr>"#
+            ),
+        })
+        .collect()
+}
+
+/// Standalone HTML receipt, carrying the signed receipt so it can still be verified
+pub fn render_html(receipt: &SignedReceipt) -> Result<String, anyhow::Error> {
+    let payload = &receipt.payload;
+    let url = transaction_url(&payload.transaction_id);
+    let qr = QrCode::new(&url)?
+        .render::<svg::Color>()
+        .min_dimensions(140, 140)
+        .build();
+    // "</" would close the script element early, "<\/" is the same JSON string
+    let data = serde_json::to_string(receipt)?.replace("</", "<\\/");
+    let role = payload.creator_role_text();
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: Helvetica, Arial, sans-serif; color: #171717; max-width: 860px; margin: 40px auto; padding: 0 24px; }}
+header {{ display: flex; justify-content: space-between; align-items: flex-start; border-bottom: 4px solid #4f46e5; padding-bottom: 16px; }}
+.brand {{ color: #4f46e5; font-size: 28px; font-weight: bold; margin: 0; }}
+.kind {{ color: #737373; margin: 4px 0 0; }}
+h2 {{ margin: 32px 0 4px; }}
+h3 {{ margin: 28px 0 8px; color: #4f46e5; }}
+p.description {{ white-space: pre-wrap; color: #404040; margin: 0; }}
+table {{ width: 100%; border-collapse: collapse; font-size: 14px; }}
+td {{ padding: 8px; border-bottom: 1px solid #e5e5e5; vertical-align: top; }}
+td:first-child {{ color: #737373; white-space: nowrap; }}
+.mono {{ font-family: Menlo, Consolas, monospace; font-size: 12px; word-break: break-all; }}
+.amount {{ text-align: right; white-space: nowrap; font-weight: bold; }}
+tr.hidden td {{ color: #a3a3a3; }}
+footer {{ margin-top: 40px; font-size: 12px; color: #737373; }}
+</style>
+</head>
+<body>
+<header>
+<div>
+<p class="brand">Snap Coin</p>
+<p class="kind">Transaction Receipt</p>
+</div>
+<a href="{url}">{qr}</a>
+</header>
+<h2>{title}</h2>
+<p class="description">{description}</p>
+<h3>Network Information</h3>
+<table>
+<tr><td>Transaction ID</td><td class="mono">{transaction_id}</td></tr>
+<tr><td>Time</td><td>{time}</td></tr>
+<tr><td>Block</td><td class="mono">#{height}: {block_hash}</td></tr>
+</table>
+<h3>Funders</h3>
+<table>{funders}</table>
+<h3>Payees</h3>
+<table>{payees}</table>
+<h3>Annotation Information</h3>
+<table>
+<tr><td>Created by</td><td class="mono">{creator}</td></tr>
+<tr><td>Creator role</td><td>{role}</td></tr>
+<tr><td>Creator signature</td><td class="mono">{signature}</td></tr>
+</table>
+<footer>This document carries its signed receipt. Load it in Verify Receipt of the Snap Coin wallet to check it against the chain.</footer>
+{HTML_RECEIPT_TAG}{data}</script>
+</body>
+</html>
+"#,
+        title = escape_html(&payload.title),
+        description = escape_html(&payload.description),
+        transaction_id = payload.transaction_id,
+        time = escape_html(&format_receipt_time(payload.timestamp)),
+        height = payload.height,
+        block_hash = payload.block_hash,
+        funders = html_rows("Funder", &rows(&payload.funders, "-")),
+        payees = html_rows("Payee", &rows(&payload.payees, "+")),
+        creator = payload.creator,
+        role = if role.is_empty() {
+            "Not stated".to_string()
+        } else {
+            role
+        },
+        signature = receipt.signature,
+    ))
+}
+
+/// The signed receipt JSON of an HTML receipt
+pub fn extract_html_receipt(html: &str) -> Option<&str> {
+    let (_, rest) = html.split_once(HTML_RECEIPT_TAG)?;
+    let (data, _) = rest.split_once("</script>")?;
+    Some(data.trim())
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn name(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+            Font::Mono => Name(b"F3"),
+        }
+    }
+
+    /// Rough average glyph width, exact for the monospaced font
+    fn char_width(self, size: f32) -> f32 {
+        match self {
+            Font::Regular | Font::Bold => size * 0.55,
+            Font::Mono => size * 0.6,
+        }
+    }
+}
+
+/// Text as WinAnsi bytes, characters the standard fonts can not draw become '?'
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Greedy word wrap, breaking words that are longer than a line
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let mut word = word.to_string();
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let rest = word.split_off(word.char_indices().nth(max_chars).unwrap().0);
+                lines.push(word);
+                word = rest;
+            }
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line += &word;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lays lines out top to bottom, starting a new page when one is full
+struct PdfLayout {
+    pages: Vec<Content>,
+    y: f32,
+    /// Lines above this height make room for the QR code
+    qr_bottom: f32,
+}
+
+impl PdfLayout {
+    fn new() -> Self {
+        PdfLayout {
+            pages: vec![Content::new()],
+            y: PAGE_HEIGHT - MARGIN,
+            qr_bottom: PAGE_HEIGHT - MARGIN - QR_SIZE,
+        }
+    }
+
+    fn content(&mut self) -> &mut Content {
+        self.pages.last_mut().unwrap()
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn text(&mut self, font: Font, size: f32, color: (f32, f32, f32), text: &str) {
+        let line_height = size * 1.4;
+        let width = if self.pages.len() == 1 && self.y - line_height > self.qr_bottom {
+            PAGE_WIDTH - 2.0 * MARGIN - QR_SIZE - 20.0
+        } else {
+            PAGE_WIDTH - 2.0 * MARGIN
+        };
+        let max_chars = (width / font.char_width(size)) as usize;
+
+        for line in wrap(text, max_chars) {
+            if self.y - line_height < MARGIN {
+                self.pages.push(Content::new());
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= line_height;
+            let y = self.y;
+            let content = self.content();
+            content.set_fill_rgb(color.0, color.1, color.2);
+            content.begin_text();
+            content.set_font(font.name(), size);
+            content.next_line(MARGIN, y);
+            content.show(Str(&win_ansi(&line)));
+            content.end_text();
+        }
+    }
+
+    /// Draws the QR code in the top right corner of the first page
+    fn qr(&mut self, code: &QrCode) {
+        let width = code.width();
+        let module = QR_SIZE / width as f32;
+        let left = PAGE_WIDTH - MARGIN - QR_SIZE;
+        let top = PAGE_HEIGHT - MARGIN;
+
+        let content = &mut self.pages[0];
+        content.set_fill_rgb(0.0, 0.0, 0.0);
+        for (i, color) in code.to_colors().into_iter().enumerate() {
+            if color == Color::Dark {
+                let (x, y) = ((i % width) as f32, (i / width) as f32);
+                content.rect(left + x * module, top - (y + 1.0) * module, module, module);
+            }
+        }
+        content.fill_nonzero();
+    }
+}
+
+fn pdf_rows(layout: &mut PdfLayout, label: &str, rows: &[Row]) {
+    for row in rows {
+        match row {
+            Row::Shown {
+                number,
+                note,
+                address,
+                amount,
+            } => {
+                let heading = if note.is_empty() {
+                    format!("{label} #{number}")
+                } else {
+                    format!("{label} #{number}: {note}")
+                };
+                layout.text(Font::Bold, 10.0, TEXT_RGB, &heading);
+                layout.text(Font::Mono, 8.0, MUTED_RGB, address);
+                layout.text(Font::Regular, 10.0, TEXT_RGB, amount);
+            }
+            Row::Hidden { number, commitment } => {
+                layout.text(
+                    Font::Bold,
+                    10.0,
+                    MUTED_RGB,
+                    &format!("{label} #{number}: hidden"),
+                );
+                layout.text(
+                    Font::Mono,
+                    8.0,
+                    MUTED_RGB,
+                    &format!("Commitment {commitment}"),
+                );
+            }
+        }
+        layout.gap(6.0);
+    }
+}
+
+/// PDF receipt, carrying the signed receipt as an attached file so it can still be verified
+pub fn render_pdf(receipt: &SignedReceipt) -> Result<Vec<u8>, anyhow::Error> {
+    let payload = &receipt.payload;
+    let code = QrCode::new(transaction_url(&payload.transaction_id))?;
+
+    let mut layout = PdfLayout::new();
+    layout.qr(&code);
+    layout.text(Font::Bold, 22.0, BRAND_RGB, "Snap Coin");
+    layout.text(Font::Regular, 11.0, MUTED_RGB, "Transaction Receipt");
+    layout.gap(24.0);
+    layout.text(Font::Bold, 16.0, TEXT_RGB, &payload.title);
+    layout.text(Font::Regular, 10.0, TEXT_RGB, &payload.description);
+
+    layout.gap(12.0);
+    layout.text(Font::Bold, 12.0, BRAND_RGB, "Network Information");
+    layout.text(Font::Regular, 10.0, MUTED_RGB, "Transaction ID");
+    layout.text(Font::Mono, 8.0, TEXT_RGB, &payload.transaction_id);
+    layout.text(Font::Regular, 10.0, MUTED_RGB, "Time");
+    layout.text(
+        Font::Regular,
+        10.0,
+        TEXT_RGB,
+        &format_receipt_time(payload.timestamp),
+    );
+    layout.text(
+        Font::Regular,
+        10.0,
+        MUTED_RGB,
+        &format!("Block #{}", payload.height),
+    );
+    layout.text(Font::Mono, 8.0, TEXT_RGB, &payload.block_hash);
+
+    layout.gap(12.0);
+    layout.text(Font::Bold, 12.0, BRAND_RGB, "Funders");
+    pdf_rows(&mut layout, "Funder", &rows(&payload.funders, "-"));
+    layout.gap(6.0);
+    layout.text(Font::Bold, 12.0, BRAND_RGB, "Payees");
+    pdf_rows(&mut layout, "Payee", &rows(&payload.payees, "+"));
+
+    layout.gap(6.0);
+    layout.text(Font::Bold, 12.0, BRAND_RGB, "Annotation Information");
+    layout.text(Font::Regular, 10.0, MUTED_RGB, "Created by");
+    layout.text(Font::Mono, 8.0, TEXT_RGB, &payload.creator);
+    if !payload.creator_roles.is_empty() {
+        layout.text(Font::Regular, 10.0, MUTED_RGB, "Creator role");
+        layout.text(Font::Regular, 10.0, TEXT_RGB, &payload.creator_role_text());
+    }
+    layout.text(Font::Regular, 10.0, MUTED_RGB, "Creator signature");
+    layout.text(Font::Mono, 8.0, TEXT_RGB, &receipt.signature);
+    layout.gap(12.0);
+    layout.text(
+        Font::Regular,
+        9.0,
+        MUTED_RGB,
+        &format!("The signed receipt is attached as {PDF_RECEIPT_FILE}. Load this document in Verify Receipt of the Snap Coin wallet to check it against the chain."),
+    );
+
+    let mut next_id = Ref::new(1);
+    let mut alloc = || next_id.bump();
+    let catalog_id = alloc();
+    let pages_id = alloc();
+    let info_id = alloc();
+    let fonts = [
+        (alloc(), Font::Regular, Name(b"Helvetica")),
+        (alloc(), Font::Bold, Name(b"Helvetica-Bold")),
+        (alloc(), Font::Mono, Name(b"Courier")),
+    ];
+    let file_id = alloc();
+    let spec_id = alloc();
+    let page_ids: Vec<(Ref, Ref)> = layout.pages.iter().map(|_| (alloc(), alloc())).collect();
+
+    let mut pdf = Pdf::new();
+    let mut catalog = pdf.catalog(catalog_id);
+    catalog.pages(pages_id);
+    catalog
+        .names()
+        .embedded_files()
+        .names()
+        .insert(Str(PDF_RECEIPT_FILE.as_bytes()), spec_id);
+    catalog.finish();
+    pdf.document_info(info_id)
+        .title(TextStr(&payload.title))
+        .producer(TextStr("Snap Coin Wallet"));
+    pdf.pages(pages_id)
+        .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+        .count(page_ids.len() as i32);
+
+    for (font_id, _, base_font) in &fonts {
+        pdf.type1_font(*font_id)
+            .base_font(*base_font)
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+
+    for ((page_id, content_id), content) in page_ids.iter().zip(layout.pages) {
+        let mut page = pdf.page(*page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+        page.parent(pages_id);
+        page.contents(*content_id);
+        let mut resources = page.resources();
+        let mut font_dict = resources.fonts();
+        for (font_id, font, _) in &fonts {
+            font_dict.pair(font.name(), *font_id);
+        }
+        font_dict.finish();
+        resources.finish();
+        page.finish();
+        pdf.stream(*content_id, &content.finish());
+    }
+
+    // Left uncompressed, so the receipt can be found again without a PDF parser
+    let data = serde_json::to_vec(receipt)?;
+    pdf.embedded_file(file_id, &data)
+        .subtype(Name(b"application#2Fjson"));
+    pdf.file_spec(spec_id)
+        .path(Str(PDF_RECEIPT_FILE.as_bytes()))
+        .description(TextStr("Signed Snap Coin receipt"))
+        .embedded_file(file_id);
+
+    Ok(pdf.finish())
+}
+
+/// The signed receipt JSON attached to a PDF receipt written by `render_pdf`
+pub fn extract_pdf_receipt(pdf: &[u8]) -> Option<String> {
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let file = find(pdf, b"/Type /EmbeddedFile")?;
+    let rest = &pdf[file..];
+    let start = find(rest, b"stream\n")? + b"stream\n".len();
+    let end = find(&rest[start..], b"\nendstream")?;
+    String::from_utf8(rest[start..start + end].to_vec()).ok()
+}
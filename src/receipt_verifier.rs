@@ -0,0 +1,286 @@
+use std::fs;
+
+use dioxus::prelude::*;
+use rfd::FileDialog;
+
+use crate::{
+    copy_box::CopyBox,
+    receipt_document::extract_pdf_receipt,
+    receipt::{
+        verify_structured_receipt, verify_text_receipt, Check, CheckStatus, EntryReveal, Receipt,
+    },
+    sync::SyncState,
+    GlobalContext,
+};
+
+pub fn status_class(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Authentic => "text-green-400",
+        CheckStatus::Altered | CheckStatus::Absent => "text-red-400",
+        CheckStatus::Hidden | CheckStatus::Unchecked => "text-neutral-400",
+    }
+}
+
+pub fn status_text(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Authentic => "Authentic",
+        CheckStatus::Altered => "Altered",
+        CheckStatus::Absent => "Absent on chain",
+        CheckStatus::Hidden => "Hidden",
+        CheckStatus::Unchecked => "Unchecked",
+    }
+}
+
+#[derive(Clone)]
+struct Verification {
+    created_by: String,
+    /// Empty when the receipt does not say
+    creator_role: String,
+    title_and_description: String,
+    signature_valid: bool,
+    checks: Vec<Check>,
+}
+
+#[component]
+pub fn ReceiptVerifier() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+
+    let mut raw = use_signal(|| "".to_string());
+    let mut verification: Signal<Option<Verification>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_busy = use_signal(|| false);
+    let mut reveals: Signal<Vec<(String, EntryReveal)>> = use_signal(Vec::new);
+
+    let verify = move |text: String| {
+        let client = global().api_client;
+        let reveals: Vec<EntryReveal> = reveals().into_iter().map(|(_, reveal)| reveal).collect();
+        spawn(async move {
+            is_busy.set(true);
+            status.set("".to_string());
+
+            let receipt = match Receipt::decode(&text) {
+                Ok(receipt) => receipt,
+                Err(e) => {
+                    verification.set(None);
+                    status.set(e.to_string());
+                    is_busy.set(false);
+                    return;
+                }
+            };
+
+            let syncing = sync.peek().syncing;
+            let result = match &receipt {
+                Receipt::Structured(receipt) => {
+                    verify_structured_receipt(&receipt.payload, &reveals, client.as_deref())
+                        .await
+                        .map(|checks| Verification {
+                            created_by: receipt.payload.creator.clone(),
+                            creator_role: receipt.payload.creator_role_text(),
+                            title_and_description: format!(
+                                "{}\n{}",
+                                receipt.payload.title, receipt.payload.description
+                            ),
+                            signature_valid: receipt.signature_valid(),
+                            checks,
+                        })
+                }
+                Receipt::Legacy(receipt) => verify_text_receipt(receipt, client.as_deref())
+                    .await
+                    .map(|checks| Verification {
+                        created_by: receipt.created_by.clone(),
+                        creator_role: String::new(),
+                        title_and_description: receipt.title_and_description.clone(),
+                        signature_valid: receipt.signature_valid(),
+                        checks,
+                    }),
+            };
+
+            match result {
+                Ok(result) => verification.set(Some(result)),
+                Err(e) => {
+                    verification.set(None);
+                    status.set(e.or_syncing(syncing).to_string());
+                }
+            }
+
+            is_busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Verify Receipt" }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+                    h3 { class: "font-semibold text-lg", "Receipt" }
+                    p { class: "text-sm text-neutral-400", "Paste or load an annotated transaction receipt, as PDF, HTML, text or JSON." }
+
+                    textarea {
+                        class: "bg-neutral-800 p-2 rounded w-full h-60 font-mono text-xs",
+                        value: "{raw}",
+                        oninput: move |e| raw.set(e.value()),
+                        placeholder: "Transaction Confirmation..."
+                    }
+
+                    button {
+                        disabled: is_busy(),
+                        onclick: move |_| verify(raw()),
+                        "Verify"
+                    }
+
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: is_busy(),
+                        onclick: move |_| {
+                            let Some(path) = FileDialog::new()
+                                .set_title("Open Receipt")
+                                .add_filter("Receipt", &["pdf", "html", "txt", "json"])
+                                .pick_file()
+                            else {
+                                return;
+                            };
+                            let text = match fs::read(&path) {
+                                // A PDF receipt is checked through the signed receipt attached to it
+                                Ok(bytes) if bytes.starts_with(b"%PDF") => extract_pdf_receipt(&bytes)
+                                    .ok_or_else(|| "This PDF does not carry a receipt".to_string()),
+                                Ok(bytes) => String::from_utf8(bytes).map_err(|_| "Not a receipt, the file is not text".to_string()),
+                                Err(e) => Err(format!("Could not read file: {e}")),
+                            };
+                            match text {
+                                Ok(text) => {
+                                    raw.set(text.clone());
+                                    verify(text);
+                                }
+                                Err(e) => status.set(e),
+                            }
+                        },
+                        "Load from file"
+                    }
+
+                    h3 { class: "font-semibold text-lg", "Reveals" }
+                    p { class: "text-sm text-neutral-400", "Reveal files disclose entries the creator hid." }
+                    for (i, (name, reveal)) in reveals().into_iter().enumerate() {
+                        div {
+                            class: "bg-neutral-800 p-2 rounded flex items-center justify-between gap-2 text-sm",
+                            span {
+                                class: "truncate",
+                                "{name}: "
+                                {reveal.role.label()}
+                                " #{reveal.index + 1}"
+                            }
+                            button {
+                                class: "bg-transparent! border! border-[var(--border)]! text-xs",
+                                disabled: is_busy(),
+                                onclick: move |_| {
+                                    reveals.write().remove(i);
+                                    if !raw().is_empty() {
+                                        verify(raw());
+                                    }
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: is_busy(),
+                        onclick: move |_| {
+                            let Some(paths) = FileDialog::new()
+                                .set_title("Open Reveals")
+                                .add_filter("JSON", &["json"])
+                                .pick_files()
+                            else {
+                                return;
+                            };
+                            for path in paths {
+                                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                                let reveal = fs::read_to_string(&path)
+                                    .map_err(anyhow::Error::from)
+                                    .and_then(|json| EntryReveal::from_json(&json));
+                                match reveal {
+                                    Ok(reveal) => {
+                                        if !reveals().iter().any(|(_, loaded)| *loaded == reveal) {
+                                            reveals.write().push((name, reveal));
+                                        }
+                                    }
+                                    Err(e) => {
+                                        status.set(format!("Could not read reveal {name}: {e}"));
+                                        return;
+                                    }
+                                }
+                            }
+                            if !raw().is_empty() {
+                                verify(raw());
+                            }
+                        },
+                        "Load reveals"
+                    }
+
+                    p { "{status}" }
+                }
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Result" }
+
+                    if let Some(result) = verification() {
+                        {
+                            let altered = result.checks.iter().any(|c| matches!(c.status, CheckStatus::Altered | CheckStatus::Absent));
+                            let unchecked = result.checks.iter().any(|c| c.status == CheckStatus::Unchecked);
+                            let (verdict_class, verdict) = if !result.signature_valid {
+                                ("text-red-400", "The receipt was changed after it was signed, or not signed by the listed creator")
+                            } else if altered {
+                                ("text-red-400", "The receipt is signed, but does not match the chain")
+                            } else if unchecked {
+                                ("text-neutral-400", "The receipt is signed, some parts could not be checked against the chain")
+                            } else {
+                                ("text-green-400", "The receipt is signed and matches the chain")
+                            };
+
+                            rsx! {
+                                p { class: "font-bold {verdict_class}", "{verdict}" }
+
+                                div {
+                                    class: "grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                                    span { class: "text-neutral-500", "Created by" }
+                                    CopyBox { class: "w-full min-w-0", text: result.created_by.clone(), title: "Receipt creator" }
+                                    if !result.creator_role.is_empty() {
+                                        span { class: "text-neutral-500", "Creator role" }
+                                        span { class: "capitalize", "{result.creator_role}" }
+                                    }
+                                    span { class: "text-neutral-500", "Creator signature" }
+                                    span {
+                                        class: if result.signature_valid { "text-green-400" } else { "text-red-400" },
+                                        if result.signature_valid { "Authentic" } else { "Altered" }
+                                    }
+                                    span { class: "text-neutral-500", "Title and description" }
+                                    span { class: "whitespace-pre-wrap", "{result.title_and_description}" }
+                                }
+
+                                div {
+                                    class: "flex flex-col gap-2 overflow-auto pr-2",
+                                    for check in result.checks.iter() {
+                                        div {
+                                            class: "bg-neutral-800 p-4 rounded grid grid-cols-[12rem_8rem_1fr] items-center gap-x-4 text-sm",
+                                            span { class: "font-bold", "{check.part}" }
+                                            span { class: status_class(check.status), "{status_text(check.status)}" }
+                                            span { class: "font-mono text-xs break-all text-neutral-400", "{check.detail}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        p { class: "text-neutral-400", "No receipt loaded" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use snap_coin::crypto::keys::Public;
+
+use crate::{
+    encryption::{decrypt_bytes, encrypt_bytes},
+    write_atomic,
+};
+
+/// Wallet preferences, stored next to the wallet file and encrypted with the same PIN
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    /// Base36 addresses of vault wallets that should not raise notifications
+    pub muted_wallets: HashSet<String>,
+    /// Notify when the in-wallet node caught up with the network
+    pub notify_node_synced: bool,
+    /// Token presented to external node APIs that require one
+    pub external_api_token: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            muted_wallets: HashSet::new(),
+            notify_node_synced: true,
+            external_api_token: "".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn notifications_enabled(&self, wallet: &Public) -> bool {
+        !self.muted_wallets.contains(&wallet.dump_base36())
+    }
+
+    pub fn set_notifications_enabled(&mut self, wallet: &Public, enabled: bool) {
+        if enabled {
+            self.muted_wallets.remove(&wallet.dump_base36());
+        } else {
+            self.muted_wallets.insert(wallet.dump_base36());
+        }
+    }
+}
+
+/// Returns settings file path
+pub fn settings_path() -> Result<PathBuf, Error> {
+    let home = dirs::home_dir().ok_or_else(|| Error::msg("Could not determine home directory"))?;
+    Ok(home.join(".snap-coin-wallet-settings"))
+}
+
+/// Load the settings, falling back to defaults when none were saved yet
+pub fn load_settings(pin: &str) -> Result<Settings, Error> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let decrypted = decrypt_bytes(&fs::read(path)?, pin)
+        .ok_or_else(|| Error::msg("Failed to decrypt settings"))?;
+    Ok(serde_json::from_slice(&decrypted)?)
+}
+
+pub fn save_settings(settings: &Settings, pin: &str) -> Result<(), Error> {
+    let encrypted = encrypt_bytes(&serde_json::to_vec(settings)?, pin)
+        .ok_or_else(|| Error::msg("Failed to encrypt settings"))?;
+    write_atomic(&settings_path()?, &encrypted)?;
+    Ok(())
+}
@@ -0,0 +1,696 @@
+use std::{collections::HashSet, fs};
+
+use anyhow::anyhow;
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use dioxus::prelude::*;
+use rfd::FileDialog;
+use serde::{Deserialize, Serialize};
+use snap_coin::{
+    api::client::Client,
+    blockchain_data_provider::BlockchainDataProvider,
+    core::transaction::TransactionId,
+    crypto::{
+        keys::{Private, Public},
+        Signature,
+    },
+    to_snap,
+};
+
+use crate::{
+    annotations::Annotations,
+    authorize::ask_for_auth,
+    chain::{require_transaction, ChainError},
+    copy_box::CopyBox,
+    explorer::TransactionLink,
+    home::{address_flows, format_timestamp_secs},
+    receipt::{Check, CheckStatus},
+    receipt_verifier::{status_class, status_text},
+    sync::SyncState,
+    GlobalContext,
+};
+
+/// Version of the statement format, bumped on incompatible changes
+pub const STATEMENT_VERSION: u32 = 1;
+
+/// Prefixed to the canonical payload before signing, so a statement signature can never double as another signature
+const STATEMENT_SIGNING_PREFIX: &[u8] = b"Snap Coin Statement:\n";
+
+/// One transaction of a statement
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct StatementLine {
+    pub transaction_id: TransactionId,
+    pub timestamp: u64,
+    /// Nano paid to the address
+    pub amount_in: u64,
+    /// Nano spent from the address
+    pub amount_out: u64,
+    pub title: String,
+    pub description: String,
+}
+
+/// Everything a statement signs, serialized as compact JSON in field order
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StatementPayload {
+    pub version: u32,
+    /// The address the statement is about, and the key that signs it
+    pub address: String,
+    /// Start of the period, inclusive unix seconds
+    pub from: u64,
+    /// End of the period, exclusive unix seconds
+    pub to: u64,
+    pub opening_balance: u64,
+    pub closing_balance: u64,
+    pub total_in: u64,
+    pub total_out: u64,
+    /// Oldest first
+    pub lines: Vec<StatementLine>,
+    /// Chain height the statement was generated at
+    pub height: u64,
+}
+
+impl StatementPayload {
+    fn signing_buf(&self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok([STATEMENT_SIGNING_PREFIX, &serde_json::to_vec(self)?].concat())
+    }
+
+    pub fn sign(self, mut private: Private) -> Result<SignedStatement, anyhow::Error> {
+        if private.to_public().dump_base36() != self.address {
+            return Err(anyhow!("A statement can only be signed by its own wallet"));
+        }
+        let signature = Signature::new_signature(&mut private, &self.signing_buf()?);
+        Ok(SignedStatement {
+            payload: self,
+            signature: signature.dump_base36(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SignedStatement {
+    pub payload: StatementPayload,
+    pub signature: String,
+}
+
+impl SignedStatement {
+    pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
+        let statement: SignedStatement = serde_json::from_str(json.trim())?;
+        if statement.payload.version != STATEMENT_VERSION {
+            return Err(anyhow!(
+                "Unsupported statement version {}",
+                statement.payload.version
+            ));
+        }
+        Ok(statement)
+    }
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Whether the signature covers the payload and was made by the wallet the statement is about
+    pub fn signature_valid(&self) -> bool {
+        let (Some(address), Some(signature), Ok(signing_buf)) = (
+            Public::new_from_base36(&self.payload.address),
+            Signature::new_from_base36(&self.signature),
+            self.payload.signing_buf(),
+        ) else {
+            return false;
+        };
+        signature
+            .validate_with_public(&address, &signing_buf)
+            .unwrap_or(false)
+    }
+}
+
+/// Every transaction of an address at or after `from`, newest first, with what it moved in and out
+async fn transactions_since(
+    client: &Client,
+    address: Public,
+    from: u64,
+) -> Result<Vec<(TransactionId, u64, u64, u64)>, ChainError> {
+    let mut transactions = vec![];
+    for transaction_id in client.get_transactions_of_address(address, None).await? {
+        let tx = require_transaction(client, &transaction_id).await?;
+        if tx.timestamp < from {
+            continue;
+        }
+        let (amount_in, amount_out) = address_flows(client, address, &tx).await?;
+        transactions.push((transaction_id, tx.timestamp, amount_in, amount_out));
+    }
+    Ok(transactions)
+}
+
+/// The balance before `since` happened, walked back from the current balance
+fn opening_balance(
+    balance: u64,
+    since: &[(TransactionId, u64, u64, u64)],
+) -> Result<u64, ChainError> {
+    let net: i128 = since
+        .iter()
+        .map(|(_, _, amount_in, amount_out)| *amount_in as i128 - *amount_out as i128)
+        .sum();
+    u64::try_from(balance as i128 - net).map_err(|_| {
+        ChainError::Node("The balance does not add up with the transaction history".to_string())
+    })
+}
+
+/// Collect everything a statement of `address` over `from..to` lists
+pub async fn build_statement(
+    client: &Client,
+    address: Public,
+    from: u64,
+    to: u64,
+    annotations: &Annotations,
+) -> Result<StatementPayload, ChainError> {
+    let height = client.get_height().await? as u64;
+    let balance = client.get_balance(address).await?;
+    let since = transactions_since(client, address, from).await?;
+    let opening_balance = opening_balance(balance, &since)?;
+
+    let mut lines: Vec<StatementLine> = since
+        .into_iter()
+        .filter(|(_, timestamp, ..)| *timestamp < to)
+        .map(|(transaction_id, timestamp, amount_in, amount_out)| {
+            let annotation = annotations
+                .get(&transaction_id)
+                .cloned()
+                .unwrap_or_default();
+            StatementLine {
+                transaction_id,
+                timestamp,
+                amount_in,
+                amount_out,
+                title: annotation.title,
+                description: annotation.description,
+            }
+        })
+        .collect();
+    lines.sort_by_key(|line| line.timestamp);
+
+    let total_in: u64 = lines.iter().map(|line| line.amount_in).sum();
+    let total_out: u64 = lines.iter().map(|line| line.amount_out).sum();
+
+    Ok(StatementPayload {
+        version: STATEMENT_VERSION,
+        address: address.dump_base36(),
+        from,
+        to,
+        opening_balance,
+        closing_balance: (opening_balance + total_in).saturating_sub(total_out),
+        total_in,
+        total_out,
+        lines,
+        height,
+    })
+}
+
+fn snap(amount: u64) -> String {
+    format!("{} SNAP", to_snap(amount))
+}
+
+/// Check a statement adds up, and with a node that it lists every transaction of the period as it happened
+pub async fn verify_statement(
+    payload: &StatementPayload,
+    client: Option<&Client>,
+) -> Result<Vec<Check>, ChainError> {
+    let mut checks = vec![];
+
+    let total_in: u64 = payload.lines.iter().map(|line| line.amount_in).sum();
+    let total_out: u64 = payload.lines.iter().map(|line| line.amount_out).sum();
+    checks.push(Check::compare(
+        "Total in",
+        &snap(payload.total_in),
+        &snap(total_in),
+    ));
+    checks.push(Check::compare(
+        "Total out",
+        &snap(payload.total_out),
+        &snap(total_out),
+    ));
+    let closing = (payload.opening_balance + total_in).saturating_sub(total_out);
+    checks.push(Check::compare(
+        "Closing balance",
+        &snap(payload.closing_balance),
+        &snap(closing),
+    ));
+
+    // A repeated line inflates the totals while every line on its own still matches the chain
+    let mut listed = HashSet::new();
+    for line in &payload.lines {
+        if !listed.insert(line.transaction_id) {
+            checks.push(Check::new(
+                format!("Transaction {}", line.transaction_id.dump_base36()),
+                CheckStatus::Altered,
+                "Listed more than once",
+            ));
+        }
+    }
+
+    let Some(client) = client else {
+        checks.push(Check::new(
+            "Transactions",
+            CheckStatus::Unchecked,
+            "Connect to a node to check the statement against the chain",
+        ));
+        return Ok(checks);
+    };
+    let Some(address) = Public::new_from_base36(&payload.address) else {
+        checks.push(Check::new(
+            "Address",
+            CheckStatus::Altered,
+            "The statement address is not valid",
+        ));
+        return Ok(checks);
+    };
+
+    let balance = client.get_balance(address).await?;
+    let since = transactions_since(client, address, payload.from).await?;
+    checks.push(Check::compare(
+        "Opening balance",
+        &snap(payload.opening_balance),
+        &snap(opening_balance(balance, &since)?),
+    ));
+
+    let (in_period, after_period): (Vec<_>, Vec<_>) = since
+        .into_iter()
+        .partition(|(_, timestamp, ..)| *timestamp < payload.to);
+    checks.push(Check::compare(
+        "Closing balance on chain",
+        &snap(payload.closing_balance),
+        &snap(opening_balance(balance, &after_period)?),
+    ));
+    for line in &payload.lines {
+        let part = format!("Transaction {}", line.transaction_id.dump_base36());
+        let Some((_, timestamp, amount_in, amount_out)) = in_period
+            .iter()
+            .find(|(transaction_id, ..)| *transaction_id == line.transaction_id)
+        else {
+            checks.push(Check::new(
+                part,
+                CheckStatus::Absent,
+                "Not a transaction of this address in the period",
+            ));
+            continue;
+        };
+        let listed = format!(
+            "{}, +{} -{}",
+            format_timestamp_secs(line.timestamp),
+            snap(line.amount_in),
+            snap(line.amount_out)
+        );
+        let on_chain = format!(
+            "{}, +{} -{}",
+            format_timestamp_secs(*timestamp),
+            snap(*amount_in),
+            snap(*amount_out)
+        );
+        checks.push(Check::compare(part, &listed, &on_chain));
+    }
+
+    // A statement that leaves transactions out is as wrong as one that changes them
+    for (transaction_id, ..) in &in_period {
+        if !payload
+            .lines
+            .iter()
+            .any(|line| line.transaction_id == *transaction_id)
+        {
+            checks.push(Check::new(
+                format!("Transaction {}", transaction_id.dump_base36()),
+                CheckStatus::Altered,
+                "On chain in the period, but missing from the statement",
+            ));
+        }
+    }
+
+    Ok(checks)
+}
+
+/// Local midnight of a `YYYY-MM-DD` date as unix seconds
+fn day_start(date: &str) -> Option<u64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let start = Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    u64::try_from(start.timestamp()).ok()
+}
+
+fn format_day(timestamp: u64) -> String {
+    Local
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map_or_else(
+            || timestamp.to_string(),
+            |day| day.format("%b %d, %Y").to_string(),
+        )
+}
+
+/// First and last day of the previous month
+fn last_month() -> (String, String) {
+    let today = Local::now().date_naive();
+    let last = today
+        .with_day(1)
+        .and_then(|first| first.pred_opt())
+        .unwrap_or(today);
+    let first = last.with_day(1).unwrap_or(last);
+    (
+        first.format("%Y-%m-%d").to_string(),
+        last.format("%Y-%m-%d").to_string(),
+    )
+}
+
+#[component]
+fn StatementView(statement: SignedStatement) -> Element {
+    let payload = statement.payload;
+    let period = format!(
+        "{} to {}",
+        format_day(payload.from),
+        format_day(payload.to.saturating_sub(1))
+    );
+
+    rsx! {
+        div {
+            class: "grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+            span { class: "text-neutral-500", "Address" }
+            CopyBox { class: "w-full min-w-0", text: payload.address.clone(), title: "Statement address" }
+            span { class: "text-neutral-500", "Period" }
+            span { "{period}" }
+            span { class: "text-neutral-500", "Opening balance" }
+            span { class: "font-mono", "{snap(payload.opening_balance)}" }
+            span { class: "text-neutral-500", "Total in" }
+            span { class: "font-mono text-green-400", "+{snap(payload.total_in)}" }
+            span { class: "text-neutral-500", "Total out" }
+            span { class: "font-mono text-red-400", "-{snap(payload.total_out)}" }
+            span { class: "text-neutral-500", "Closing balance" }
+            span { class: "font-mono font-bold", "{snap(payload.closing_balance)}" }
+        }
+
+        div {
+            class: "flex flex-col gap-2 overflow-auto pr-2",
+            if payload.lines.is_empty() {
+                p { class: "text-neutral-400", "No transactions in this period" }
+            }
+            for line in payload.lines {
+                div {
+                    class: "bg-neutral-800 p-4 rounded grid grid-cols-[10rem_1fr_8rem_8rem] items-center gap-x-4 text-sm",
+                    span { class: "text-neutral-500 text-xs", "{format_timestamp_secs(line.timestamp)}" }
+                    div {
+                        class: "flex flex-col min-w-0",
+                        if !line.title.is_empty() {
+                            span { class: "font-bold truncate", title: "{line.description}", "{line.title}" }
+                        }
+                        TransactionLink { transaction_id: line.transaction_id, class: "truncate" }
+                    }
+                    span { class: "font-mono text-green-400 text-right", if line.amount_in > 0 { "+{to_snap(line.amount_in)}" } }
+                    span { class: "font-mono text-red-400 text-right", if line.amount_out > 0 { "-{to_snap(line.amount_out)}" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn StatementGenerator() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+
+    let (first, last) = last_month();
+    let mut wallet = use_signal(|| global().selected_wallet);
+    let mut from = use_signal(|| first);
+    let mut to = use_signal(|| last);
+    let mut statement: Signal<Option<SignedStatement>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_busy = use_signal(|| false);
+
+    use_effect(move || {
+        wallet.read();
+        from.read();
+        to.read();
+        statement.set(None);
+    });
+
+    let mut wallet_names: Vec<String> = global().wallets.keys().cloned().collect();
+    wallet_names.sort();
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Account Statement" }
+            p { "Sign a statement of everything a wallet received and spent over a period." }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+                    label { "Wallet" }
+                    select {
+                        value: "{wallet}",
+                        onchange: move |e| wallet.set(e.value()),
+                        for name in wallet_names {
+                            option { value: "{name}", "{name}" }
+                        }
+                    }
+                    label { "From" }
+                    input {
+                        r#type: "date",
+                        value: "{from}",
+                        oninput: move |e| from.set(e.value()),
+                    }
+                    label { "To" }
+                    input {
+                        r#type: "date",
+                        value: "{to}",
+                        oninput: move |e| to.set(e.value()),
+                    }
+
+                    button {
+                        disabled: is_busy(),
+                        onclick: move |_| {
+                            let Some(client) = global().api_client else {
+                                status.set("Connect to a node first".to_string());
+                                return;
+                            };
+                            let Some(private) = global().wallets.get(&wallet()).copied() else {
+                                status.set("Choose a wallet".to_string());
+                                return;
+                            };
+                            // The end date is included, so the period runs until the next midnight
+                            let (Some(start), Some(end)) = (day_start(&from()), day_start(&to()).map(|end| end + 24 * 60 * 60)) else {
+                                status.set("Choose a valid period".to_string());
+                                return;
+                            };
+                            if end <= start {
+                                status.set("The period ends before it starts".to_string());
+                                return;
+                            }
+
+                            spawn(async move {
+                                if !ask_for_auth().await {
+                                    status.set("Unauthorized".to_string());
+                                    return;
+                                }
+                                is_busy.set(true);
+                                status.set("Collecting transactions...".to_string());
+                                let syncing = sync.peek().syncing;
+                                let annotations = global.peek().annotations.clone();
+
+                                match build_statement(&client, private.to_public(), start, end, &annotations).await {
+                                    Ok(payload) => match payload.sign(private) {
+                                        Ok(signed) => {
+                                            statement.set(Some(signed));
+                                            status.set("".to_string());
+                                        }
+                                        Err(e) => status.set(format!("Failed to sign statement: {e}")),
+                                    },
+                                    Err(e) => status.set(e.or_syncing(syncing).to_string()),
+                                }
+                                is_busy.set(false);
+                            });
+                        },
+                        if is_busy() { "Generating..." } else { "Generate and sign" }
+                    }
+
+                    if let Some(signed) = statement() {
+                        button {
+                            class: "bg-transparent! border! border-[var(--border)]!",
+                            onclick: move |_| {
+                                let json = match signed.to_json() {
+                                    Ok(json) => json,
+                                    Err(e) => {
+                                        status.set(format!("Failed to export statement: {e}"));
+                                        return;
+                                    }
+                                };
+                                if let Some(path) = FileDialog::new()
+                                    .set_title("Save Statement")
+                                    .set_file_name(format!("statement-{}-{}-{}.json", &signed.payload.address[0..8], from(), to()))
+                                    .add_filter("JSON", &["json"])
+                                    .save_file()
+                                {
+                                    if let Err(e) = fs::write(&path, json) {
+                                        status.set(format!("Failed to save file: {e}"));
+                                    }
+                                }
+                            },
+                            "Download"
+                        }
+                    }
+
+                    p { "{status}" }
+                }
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Statement" }
+                    if let Some(signed) = statement() {
+                        StatementView { statement: signed }
+                    } else {
+                        p { class: "text-neutral-400", "No statement generated" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct StatementVerification {
+    statement: SignedStatement,
+    signature_valid: bool,
+    checks: Vec<Check>,
+}
+
+#[component]
+pub fn StatementVerifier() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+    let sync = consume_context::<Signal<SyncState>>();
+
+    let mut raw = use_signal(|| "".to_string());
+    let mut verification: Signal<Option<StatementVerification>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_busy = use_signal(|| false);
+
+    let verify = move |text: String| {
+        let client = global().api_client;
+        spawn(async move {
+            is_busy.set(true);
+            status.set("".to_string());
+            verification.set(None);
+
+            match SignedStatement::from_json(&text) {
+                Ok(statement) => {
+                    let syncing = sync.peek().syncing;
+                    match verify_statement(&statement.payload, client.as_deref()).await {
+                        Ok(checks) => verification.set(Some(StatementVerification {
+                            signature_valid: statement.signature_valid(),
+                            statement,
+                            checks,
+                        })),
+                        Err(e) => status.set(e.or_syncing(syncing).to_string()),
+                    }
+                }
+                Err(e) => status.set(format!("Not a statement: {e}")),
+            }
+
+            is_busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Verify Statement" }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+                    h3 { class: "font-semibold text-lg", "Statement" }
+                    p { class: "text-sm text-neutral-400", "Paste or load a signed account statement." }
+
+                    textarea {
+                        class: "bg-neutral-800 p-2 rounded w-full h-60 font-mono text-xs",
+                        value: "{raw}",
+                        oninput: move |e| raw.set(e.value()),
+                        placeholder: "Statement JSON..."
+                    }
+
+                    button {
+                        disabled: is_busy(),
+                        onclick: move |_| verify(raw()),
+                        if is_busy() { "Verifying..." } else { "Verify" }
+                    }
+
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: is_busy(),
+                        onclick: move |_| {
+                            let Some(path) = FileDialog::new()
+                                .set_title("Open Statement")
+                                .add_filter("JSON", &["json"])
+                                .pick_file()
+                            else {
+                                return;
+                            };
+                            match fs::read_to_string(&path) {
+                                Ok(text) => {
+                                    raw.set(text.clone());
+                                    verify(text);
+                                }
+                                Err(e) => status.set(format!("Could not read file: {e}")),
+                            }
+                        },
+                        "Load from file"
+                    }
+
+                    p { "{status}" }
+                }
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Result" }
+
+                    if let Some(result) = verification() {
+                        {
+                            let altered = result.checks.iter().any(|c| matches!(c.status, CheckStatus::Altered | CheckStatus::Absent));
+                            let unchecked = result.checks.iter().any(|c| c.status == CheckStatus::Unchecked);
+                            let (verdict_class, verdict) = if !result.signature_valid {
+                                ("text-red-400", "The statement was changed after it was signed, or not signed by its wallet")
+                            } else if altered {
+                                ("text-red-400", "The statement is signed, but does not match the chain")
+                            } else if unchecked {
+                                ("text-neutral-400", "The statement is signed and adds up, it was not checked against the chain")
+                            } else {
+                                ("text-green-400", "The statement is signed and matches the chain")
+                            };
+
+                            rsx! {
+                                p { class: "font-bold {verdict_class}", "{verdict}" }
+
+                                div {
+                                    class: "flex flex-col gap-4 overflow-auto pr-2",
+                                    StatementView { statement: result.statement.clone() }
+
+                                    h3 { class: "font-semibold text-lg", "Checks" }
+                                    for check in result.checks.iter() {
+                                        div {
+                                            class: "bg-neutral-800 p-4 rounded grid grid-cols-[12rem_8rem_1fr] items-center gap-x-4 text-sm",
+                                            span { class: "font-bold truncate", "{check.part}" }
+                                            span { class: status_class(check.status), "{status_text(check.status)}" }
+                                            span { class: "font-mono text-xs break-all text-neutral-400", "{check.detail}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        p { class: "text-neutral-400", "No statement loaded" }
+                    }
+                }
+            }
+        }
+    }
+}
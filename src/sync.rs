@@ -0,0 +1,364 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use dioxus::prelude::*;
+use snap_coin::{
+    api::client::Client,
+    blockchain_data_provider::BlockchainDataProvider,
+    core::transaction::{Transaction, TransactionId},
+    crypto::keys::Public,
+    full_node::node_state::ChainEvent,
+};
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        Notify,
+    },
+    time::sleep,
+};
+
+use crate::{
+    chain::{require_transaction, ChainError},
+    node_api::connect_api,
+    GlobalContext,
+};
+
+/// How often the chain is polled when no in-wallet node pushes events
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the chain is polled as a fallback while the in-wallet node pushes events
+const NODE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub enum SyncEvent {
+    /// A transaction touching a vault wallet was seen
+    /// `confirmed` is false while the transaction is only in the mempool
+    Transaction {
+        wallet: Public,
+        transaction_id: TransactionId,
+        transaction: Transaction,
+        confirmed: bool,
+    },
+    /// A mempool transaction expired without being included in a block
+    TransactionExpired { transaction_id: TransactionId },
+    /// The in-wallet node caught up with the network
+    NodeSynced,
+}
+
+/// Chain state kept up to date by the sync service
+#[derive(Clone)]
+pub struct SyncState {
+    pub height: usize,
+    /// Confirmed balance of every vault wallet
+    pub balances: HashMap<Public, u64>,
+    /// Bumped every time the synced state changed, screens reload their own data when it does
+    pub revision: u64,
+    /// Whether the in-wallet node is catching up with the network
+    pub syncing: bool,
+    pub error: Option<String>,
+    events: broadcast::Sender<SyncEvent>,
+    wake: Arc<Notify>,
+}
+
+impl SyncState {
+    fn new() -> Self {
+        SyncState {
+            height: 0,
+            balances: HashMap::new(),
+            revision: 0,
+            syncing: false,
+            error: None,
+            events: broadcast::channel(64).0,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Subscribe to new block and transaction events
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// Ask the sync service to sync right away, instead of waiting for the next event
+pub fn request_sync() {
+    consume_context::<Signal<SyncState>>()
+        .peek()
+        .wake
+        .notify_one();
+}
+
+/// Provide the sync state and start the sync service, called once by the app root
+pub fn use_sync_service(global: Signal<GlobalContext>) -> Signal<SyncState> {
+    let sync = use_context_provider(|| Signal::new(SyncState::new()));
+
+    // Sync right away when connecting, or when wallets are added or removed
+    let watched = use_memo(move || {
+        let global = global.read();
+        (
+            global.api_client.as_ref().map(|client| client.node),
+            global.wallets.len(),
+        )
+    });
+    use_effect(move || {
+        watched();
+        sync.peek().wake.notify_one();
+    });
+
+    use_future(move || run(global, sync));
+
+    sync
+}
+
+/// Vault wallets that are a sender or a receiver of a transaction
+fn touched_wallets(transaction: &Transaction, wallets: &HashMap<Public, String>) -> Vec<Public> {
+    let mut touched = vec![];
+    let parties = transaction
+        .inputs
+        .iter()
+        .map(|input| input.output_owner)
+        .chain(transaction.outputs.iter().map(|output| output.receiver));
+    for party in parties {
+        if wallets.contains_key(&party) && !touched.contains(&party) {
+            touched.push(party);
+        }
+    }
+    touched
+}
+
+/// Wait for the next event of the in-wallet node, never returns without one
+/// Returns None when events were missed, or the node went away
+async fn next_chain_event(
+    events: &mut Option<broadcast::Receiver<ChainEvent>>,
+) -> Option<ChainEvent> {
+    let Some(receiver) = events else {
+        return std::future::pending().await;
+    };
+    match receiver.recv().await {
+        Ok(event) => Some(event),
+        Err(RecvError::Lagged(_)) => None,
+        Err(RecvError::Closed) => {
+            *events = None;
+            None
+        }
+    }
+}
+
+/// Forward mempool events of the in-wallet node, blocks are picked up by the next sync
+fn publish_chain_event(
+    event: ChainEvent,
+    wallets: &HashMap<Public, String>,
+    sync: Signal<SyncState>,
+) {
+    let sender = sync.peek().events.clone();
+    match event {
+        ChainEvent::Transaction { transaction } => {
+            let Some(transaction_id) = transaction.transaction_id else {
+                return;
+            };
+            for wallet in touched_wallets(&transaction, wallets) {
+                let _ = sender.send(SyncEvent::Transaction {
+                    wallet,
+                    transaction_id,
+                    transaction: transaction.clone(),
+                    confirmed: false,
+                });
+            }
+        }
+        ChainEvent::TransactionExpiration { transaction } => {
+            let _ = sender.send(SyncEvent::TransactionExpired {
+                transaction_id: transaction,
+            });
+        }
+        ChainEvent::Block { .. } => {}
+    }
+}
+
+async fn run(global: Signal<GlobalContext>, mut sync: Signal<SyncState>) {
+    let wake = sync.peek().wake.clone();
+
+    // The service uses its own connection, requests sent concurrently over the screens client could get each others responses
+    let mut client: Option<Client> = None;
+    let mut chain_events: Option<broadcast::Receiver<ChainEvent>> = None;
+    let mut known: HashMap<Public, HashSet<TransactionId>> = HashMap::new();
+    // Wallets with transactions that could not be fetched yet, looked at again on the next round
+    let mut unresolved: HashSet<Public> = HashSet::new();
+    // Node `known` was collected from, reconnecting to the same node after a failed round keeps it
+    let mut known_node: Option<SocketAddr> = None;
+    let mut forced = true;
+
+    loop {
+        let (api_client, api_token, internal_node, wallets) = {
+            let global = global.peek();
+            (
+                global.api_client.clone(),
+                global.api_token.clone(),
+                global.internal_node.clone(),
+                global.wallet_names(),
+            )
+        };
+
+        match &api_client {
+            None => {
+                client = None;
+                known.clear();
+                unresolved.clear();
+                known_node = None;
+            }
+            Some(api_client) if client.as_ref().map(|c| c.node) != Some(api_client.node) => {
+                if known_node != Some(api_client.node) {
+                    known.clear();
+                    unresolved.clear();
+                    known_node = Some(api_client.node);
+                }
+                client = match connect_api(api_client.node, api_token.as_deref()).await {
+                    Ok(client) => Some(client),
+                    Err(e) => {
+                        let mut state = sync.write();
+                        state.error = Some(format!("Could not connect to node: {e}"));
+                        state.revision += 1;
+                        None
+                    }
+                };
+            }
+            Some(_) => {}
+        }
+
+        match &internal_node {
+            Some(node) if chain_events.is_none() => {
+                chain_events = Some(node.node_state.chain_events.subscribe())
+            }
+            None => chain_events = None,
+            Some(_) => {}
+        }
+
+        let syncing = match &internal_node {
+            Some(node) => *node.node_state.is_syncing.read().await,
+            None => false,
+        };
+
+        if let Some(connected) = &client {
+            if let Err(e) = sync_once(
+                connected,
+                &wallets,
+                &mut known,
+                &mut unresolved,
+                sync,
+                syncing,
+                forced,
+            )
+            .await
+            {
+                let mut state = sync.write();
+                state.error = Some(e.to_string());
+                state.revision += 1;
+                client = None; // Reconnect on the next round
+            }
+        }
+
+        // A stopped node can only be restarted once nothing holds on to its chain, and this may wait a minute
+        drop(internal_node);
+
+        let interval = if chain_events.is_some() {
+            NODE_POLL_INTERVAL
+        } else {
+            POLL_INTERVAL
+        };
+        forced = tokio::select! {
+            _ = wake.notified() => true,
+            _ = sleep(interval) => false,
+            event = next_chain_event(&mut chain_events) => {
+                if let Some(event) = event {
+                    publish_chain_event(event, &wallets, sync);
+                }
+                false
+            }
+        };
+    }
+}
+
+/// Fetch height and balances, and the latest transactions of every wallet when a new block arrived
+async fn sync_once(
+    client: &Client,
+    wallets: &HashMap<Public, String>,
+    known: &mut HashMap<Public, HashSet<TransactionId>>,
+    unresolved: &mut HashSet<Public>,
+    mut sync: Signal<SyncState>,
+    syncing: bool,
+    forced: bool,
+) -> Result<(), ChainError> {
+    let height = client.get_height().await?;
+    let mut balances = HashMap::new();
+    for public in wallets.keys() {
+        balances.insert(*public, client.get_balance(*public).await?);
+    }
+
+    let sender = sync.peek().events.clone();
+    let mut events = vec![];
+    let new_block = height != sync.peek().height;
+
+    known.retain(|public, _| wallets.contains_key(public));
+    unresolved.retain(|public| wallets.contains_key(public));
+    for public in wallets.keys() {
+        if !new_block && known.contains_key(public) && !unresolved.contains(public) {
+            continue;
+        }
+        let transactions = client.get_transactions_of_address(*public, Some(1)).await?;
+        match known.get_mut(public) {
+            Some(seen) => {
+                unresolved.remove(public);
+                for transaction_id in transactions {
+                    if seen.contains(&transaction_id) {
+                        continue;
+                    }
+                    // One transaction the node can not return yet does not cost the others their notification
+                    match require_transaction(client, &transaction_id).await {
+                        // Sent right away, so a failure later in the round does not lose it
+                        Ok(transaction) => {
+                            let _ = sender.send(SyncEvent::Transaction {
+                                wallet: *public,
+                                transaction_id,
+                                transaction,
+                                confirmed: true,
+                            });
+                            seen.insert(transaction_id);
+                        }
+                        Err(_) => {
+                            unresolved.insert(*public);
+                        }
+                    }
+                }
+            }
+            // First time seeing this wallet, everything is old news
+            None => {
+                known.insert(*public, transactions.into_iter().collect());
+            }
+        }
+    }
+
+    if sync.peek().syncing && !syncing {
+        events.push(SyncEvent::NodeSynced);
+    }
+
+    let changed = {
+        let state = sync.peek();
+        new_block || balances != state.balances || syncing != state.syncing || state.error.is_some()
+    };
+    if changed || forced {
+        let mut state = sync.write();
+        state.height = height;
+        state.balances = balances;
+        state.syncing = syncing;
+        state.error = None;
+        state.revision += 1;
+    }
+
+    for event in events {
+        let _ = sender.send(event);
+    }
+
+    Ok(())
+}
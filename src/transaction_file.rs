@@ -0,0 +1,299 @@
+use std::{fs, path::Path};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use snap_coin::{
+    blockchain_data_provider::BlockchainDataProvider,
+    core::transaction::{Transaction, TransactionInput, TransactionOutput, MAX_TRANSACTION_IO},
+    crypto::{
+        keys::{Private, Public},
+        Signature,
+    },
+    economics::EXPIRATION_TIME,
+    UtilError,
+};
+
+/// Version of the transaction file format, bumped on incompatible changes
+pub const TRANSACTION_FILE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionFileKind {
+    /// Built by a watch-only instance, waiting for the offline signer
+    Unsigned,
+    /// Signed by the offline signer, waiting for PoW and broadcast
+    Signed,
+}
+
+/// A transaction moved between an online and an offline wallet
+/// Serialized as JSON
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TransactionFile {
+    pub version: u32,
+    pub kind: TransactionFileKind,
+    pub transaction: Transaction,
+    /// Amount of every input, in the same order as the transaction inputs, so the offline signer can review the transaction without chain access
+    pub input_amounts: Vec<u64>,
+}
+
+impl TransactionFile {
+    pub fn new(
+        kind: TransactionFileKind,
+        transaction: Transaction,
+        input_amounts: Vec<u64>,
+    ) -> Self {
+        TransactionFile {
+            version: TRANSACTION_FILE_VERSION,
+            kind,
+            transaction,
+            input_amounts,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
+        let file: TransactionFile = serde_json::from_str(json)?;
+        if file.version != TRANSACTION_FILE_VERSION {
+            return Err(anyhow!(
+                "Unsupported transaction file version {}",
+                file.version
+            ));
+        }
+        if file.input_amounts.len() != file.transaction.inputs.len() {
+            return Err(anyhow!(
+                "Transaction file input amounts do not match its inputs"
+            ));
+        }
+        Ok(file)
+    }
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Sum of all input amounts
+    pub fn total_in(&self) -> u64 {
+        self.input_amounts.iter().sum()
+    }
+
+    /// Sum of all output amounts
+    pub fn total_out(&self) -> u64 {
+        self.transaction.outputs.iter().map(|o| o.amount).sum()
+    }
+
+    /// Nodes refuse the transaction after this, its timestamp is covered by the input signatures
+    pub fn expires_at(&self) -> u64 {
+        self.transaction.timestamp + EXPIRATION_TIME
+    }
+
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() as u64 > self.expires_at()
+    }
+}
+
+/// Build a new unsigned transaction, the same way `build_transaction` does, without needing the senders private key
+/// Inputs in `ignore_inputs` are left alone, they are spent by transactions that are not on chain yet
+/// Returns the transaction and the amount of every input
+pub async fn build_unsigned_transaction<B>(
+    blockchain_data_provider: &B,
+    sender: Public,
+    mut receivers: Vec<(Public, u64)>,
+    ignore_inputs: &[TransactionInput],
+) -> Result<(Transaction, Vec<u64>), UtilError>
+where
+    B: BlockchainDataProvider,
+{
+    let target_balance = receivers.iter().map(|receiver| receiver.1).sum::<u64>();
+
+    let mut available_inputs = blockchain_data_provider
+        .get_available_transaction_outputs(sender)
+        .await?;
+    available_inputs.retain(|(transaction, _, index)| {
+        !ignore_inputs
+            .iter()
+            .any(|ignored| ignored.output_index == *index && ignored.transaction_id == *transaction)
+    });
+
+    let mut used_inputs = vec![];
+    let mut current_funds = 0u64;
+    for (transaction, output, index) in available_inputs {
+        current_funds += output.amount;
+        used_inputs.push((transaction, output, index));
+        if current_funds >= target_balance {
+            break;
+        }
+    }
+
+    if target_balance > current_funds {
+        return Err(UtilError::InsufficientFunds);
+    }
+
+    if target_balance < current_funds {
+        receivers.push((sender, current_funds - target_balance));
+    }
+
+    if used_inputs.len() + receivers.len() > MAX_TRANSACTION_IO {
+        return Err(UtilError::TooMuchIO);
+    }
+
+    used_inputs.sort_by_key(|input| input.1.amount);
+
+    let transaction = Transaction::new_transaction_now(
+        used_inputs
+            .iter()
+            .map(|input| TransactionInput {
+                transaction_id: input.0,
+                output_index: input.2,
+                signature: None,
+                output_owner: sender,
+            })
+            .collect(),
+        receivers
+            .iter()
+            .map(|receiver| TransactionOutput {
+                amount: receiver.1,
+                receiver: receiver.0,
+            })
+            .collect(),
+        &mut vec![], // Left unsigned
+    )?;
+
+    Ok((
+        transaction,
+        used_inputs.iter().map(|input| input.1.amount).collect(),
+    ))
+}
+
+/// Sign every input of a transaction that is owned by `private`
+/// The first signer stamps the transaction with the current time, its expiry counts from signing instead of building
+/// Returns how many inputs were signed
+pub fn sign_transaction(
+    transaction: &mut Transaction,
+    mut private: Private,
+) -> Result<usize, anyhow::Error> {
+    if transaction
+        .inputs
+        .iter()
+        .all(|input| input.signature.is_none())
+    {
+        transaction.timestamp = chrono::Utc::now().timestamp() as u64;
+    }
+    let signing_buf = transaction.get_input_signing_buf()?;
+    let public = private.to_public();
+
+    let mut signed = 0;
+    for input in transaction
+        .inputs
+        .iter_mut()
+        .filter(|input| input.output_owner == public)
+    {
+        input.signature = Some(Signature::new_signature(&mut private, &signing_buf));
+        signed += 1;
+    }
+
+    Ok(signed)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SignatureStatus {
+    Missing,
+    Valid,
+    Invalid,
+}
+
+/// Check the signature of every input against its output owner
+pub fn verify_signatures(transaction: &Transaction) -> Result<Vec<SignatureStatus>, anyhow::Error> {
+    let signing_buf = transaction.get_input_signing_buf()?;
+
+    Ok(transaction
+        .inputs
+        .iter()
+        .map(|input| match input.signature {
+            None => SignatureStatus::Missing,
+            Some(signature) => {
+                if signature
+                    .validate_with_public(&input.output_owner, &signing_buf)
+                    .unwrap_or(false)
+                {
+                    SignatureStatus::Valid
+                } else {
+                    SignatureStatus::Invalid
+                }
+            }
+        })
+        .collect())
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Decode a transaction produced elsewhere
+/// Accepts a transaction file, a JSON transaction, or a hex encoded bincode transaction
+/// Returns the transaction, and its input amounts if the source carried them
+pub fn decode_transaction(text: &str) -> Result<(Transaction, Option<Vec<u64>>), anyhow::Error> {
+    let text = text.trim();
+
+    if let Ok(file) = TransactionFile::from_json(text) {
+        return Ok((file.transaction, Some(file.input_amounts)));
+    }
+    if let Ok(transaction) = serde_json::from_str::<Transaction>(text) {
+        return Ok((transaction, None));
+    }
+    if let Some(bytes) = decode_hex(text) {
+        let (transaction, _) =
+            bincode::decode_from_slice::<Transaction, _>(&bytes, bincode::config::standard())?;
+        return Ok((transaction, None));
+    }
+
+    Err(anyhow!(
+        "Not a transaction file, JSON transaction, or hex encoded transaction"
+    ))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowStatus {
+    /// No PoW was computed yet
+    Missing,
+    /// The transaction id does not match the transaction contents
+    InvalidHash,
+    /// The transaction id does not meet the live transaction difficulty
+    InsufficientDifficulty,
+    /// The transaction id is correct, but no difficulty was available to check it against
+    Unchecked,
+    Valid,
+}
+
+/// Check the PoW of a transaction, optionally against the live transaction difficulty
+pub fn check_pow(
+    transaction: &Transaction,
+    live_difficulty: Option<&[u8; 32]>,
+) -> Result<PowStatus, anyhow::Error> {
+    let Some(transaction_id) = transaction.transaction_id else {
+        return Ok(PowStatus::Missing);
+    };
+    if !transaction_id.compare_with_data(&transaction.get_tx_hashing_buf()?) {
+        return Ok(PowStatus::InvalidHash);
+    }
+
+    // Both are big endian, so comparing the buffers compares the numbers
+    Ok(match live_difficulty {
+        None => PowStatus::Unchecked,
+        Some(difficulty) if *transaction_id > *difficulty => PowStatus::InsufficientDifficulty,
+        Some(_) => PowStatus::Valid,
+    })
+}
@@ -0,0 +1,316 @@
+use std::{fs, sync::Arc};
+
+use dioxus::prelude::*;
+use rfd::FileDialog;
+use snap_coin::{api::client::Client, core::transaction::Transaction, to_snap};
+
+use crate::{
+    copy_box::CopyBox,
+    home::format_timestamp_secs,
+    transaction_file::{
+        check_pow, decode_transaction, verify_signatures, PowStatus, SignatureStatus,
+    },
+    GlobalContext,
+};
+
+#[derive(Clone)]
+struct Inspection {
+    transaction: Transaction,
+    input_amounts: Vec<Option<u64>>,
+    signatures: Vec<SignatureStatus>,
+    pow: PowStatus,
+    on_chain: bool,
+}
+
+/// Check everything that can be checked locally, and with a client, what the chain knows about the transaction
+async fn inspect(
+    transaction: Transaction,
+    known_amounts: Option<Vec<u64>>,
+    client: Option<Arc<Client>>,
+) -> Result<Inspection, anyhow::Error> {
+    let signatures = verify_signatures(&transaction)?;
+
+    let mut input_amounts: Vec<Option<u64>> = match known_amounts {
+        Some(amounts) => amounts.into_iter().map(Some).collect(),
+        None => vec![None; transaction.inputs.len()],
+    };
+
+    let Some(client) = client else {
+        return Ok(Inspection {
+            pow: check_pow(&transaction, None)?,
+            transaction,
+            input_amounts,
+            signatures,
+            on_chain: false,
+        });
+    };
+
+    for (input, amount) in transaction.inputs.iter().zip(input_amounts.iter_mut()) {
+        if amount.is_none() {
+            *amount = client
+                .get_transaction(&input.transaction_id)
+                .await?
+                .and_then(|funding| funding.outputs.get(input.output_index).map(|o| o.amount));
+        }
+    }
+
+    let on_chain = match transaction.transaction_id {
+        Some(transaction_id) => client.get_transaction(&transaction_id).await?.is_some(),
+        None => false,
+    };
+
+    Ok(Inspection {
+        pow: check_pow(
+            &transaction,
+            Some(&client.get_live_transaction_difficulty().await?),
+        )?,
+        transaction,
+        input_amounts,
+        signatures,
+        on_chain,
+    })
+}
+
+#[component]
+pub fn TransactionTool() -> Element {
+    let global = consume_context::<Signal<GlobalContext>>();
+
+    let mut raw = use_signal(|| "".to_string());
+    let mut inspection: Signal<Option<Inspection>> = use_signal(|| None);
+    let mut status = use_signal(|| "".to_string());
+    let mut is_busy = use_signal(|| false);
+    let mut submitted = use_signal(|| false);
+
+    let decode = move |text: String| {
+        let client = global().api_client;
+        spawn(async move {
+            is_busy.set(true);
+            submitted.set(false);
+            status.set("".to_string());
+
+            let result = async {
+                let (transaction, known_amounts) = decode_transaction(&text)?;
+                inspect(transaction, known_amounts, client).await
+            }
+            .await;
+            match result {
+                Ok(result) => inspection.set(Some(result)),
+                Err(e) => {
+                    inspection.set(None);
+                    status.set(format!("Could not decode transaction: {e}"));
+                }
+            }
+
+            is_busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "w-full h-full p-6 text-white flex flex-col gap-6",
+            h1 { class: "text-2xl font-bold text-nowrap", "Transaction Inspector" }
+
+            div {
+                class: "grid grid-cols-3 gap-6 flex-1",
+
+                div {
+                    class: "col-span-1 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4",
+                    h3 { class: "font-semibold text-lg", "Import" }
+                    p { class: "text-sm text-neutral-400", "Paste a transaction file, a JSON transaction, or a hex encoded transaction." }
+
+                    textarea {
+                        class: "bg-neutral-800 p-2 rounded w-full h-60 font-mono text-xs",
+                        value: "{raw}",
+                        oninput: move |e| raw.set(e.value()),
+                        placeholder: "Serialized transaction..."
+                    }
+
+                    button {
+                        disabled: is_busy(),
+                        onclick: move |_| decode(raw()),
+                        "Decode"
+                    }
+
+                    button {
+                        class: "bg-transparent! border! border-[var(--border)]!",
+                        disabled: is_busy(),
+                        onclick: move |_| {
+                            let Some(path) = FileDialog::new().set_title("Open Transaction").pick_file() else {
+                                return;
+                            };
+                            match fs::read_to_string(&path) {
+                                Ok(text) => {
+                                    raw.set(text.clone());
+                                    decode(text);
+                                }
+                                Err(e) => status.set(format!("Could not read file: {e}")),
+                            }
+                        },
+                        "Load from file"
+                    }
+
+                    p { "{status}" }
+                }
+
+                div {
+                    class: "col-span-2 bg-neutral-900 rounded-xl p-6 shadow flex flex-col gap-4 overflow-hidden",
+                    h3 { class: "font-semibold text-lg", "Transaction" }
+
+                    if let Some(result) = inspection() {
+                        {
+                            let transaction = result.transaction.clone();
+                            let signatures_valid = result.signatures.iter().all(|s| *s == SignatureStatus::Valid);
+                            let pow_text = match result.pow {
+                                PowStatus::Missing => "Not computed",
+                                PowStatus::InvalidHash => "Invalid, transaction id does not match its contents",
+                                PowStatus::InsufficientDifficulty => "Does not meet the live transaction difficulty",
+                                PowStatus::Unchecked => "Hash valid, connect to a node to check difficulty",
+                                PowStatus::Valid => "Valid",
+                            };
+                            let needs_pow = matches!(result.pow, PowStatus::Missing | PowStatus::InvalidHash | PowStatus::InsufficientDifficulty);
+                            let can_submit = signatures_valid && result.pow == PowStatus::Valid && !result.on_chain;
+
+                            rsx! {
+                                div {
+                                    class: "grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                                    span { class: "text-neutral-500", "Transaction ID" }
+                                    if let Some(transaction_id) = transaction.transaction_id {
+                                        CopyBox { class: "w-full min-w-0", text: transaction_id.dump_base36(), title: "Transaction ID" }
+                                    } else {
+                                        span { "None" }
+                                    }
+                                    span { class: "text-neutral-500", "Created" }
+                                    span { "{format_timestamp_secs(transaction.timestamp)}" }
+                                    span { class: "text-neutral-500", "PoW" }
+                                    span { class: if result.pow == PowStatus::Valid { "text-green-400" } else { "text-red-400" }, "{pow_text}" }
+                                    span { class: "text-neutral-500", "Signatures" }
+                                    span { class: if signatures_valid { "text-green-400" } else { "text-red-400" }, if signatures_valid { "All valid" } else { "Missing or invalid" } }
+                                    span { class: "text-neutral-500", "On chain" }
+                                    span { if result.on_chain { "Already included in a block" } else { "No" } }
+                                }
+
+                                div {
+                                    class: "flex flex-col gap-2 overflow-auto pr-2",
+                                    h4 { "Inputs" }
+                                    for (i, input) in transaction.inputs.iter().enumerate() {
+                                        div {
+                                            class: "bg-neutral-800 p-4 rounded grid grid-cols-[max-content_1fr] items-center gap-x-4 gap-y-2 text-sm",
+                                            span { class: "text-neutral-500", "Owner" }
+                                            CopyBox { class: "w-full min-w-0", text: input.output_owner.dump_base36(), title: "Input owner" }
+                                            span { class: "text-neutral-500", "Funded by" }
+                                            CopyBox { class: "w-full min-w-0", text: format!("{}:{}", input.transaction_id.dump_base36(), input.output_index), title: "Funding transaction and output index" }
+                                            span { class: "text-neutral-500", "Signature" }
+                                            if let Some(signature) = input.signature {
+                                                CopyBox { class: "w-full min-w-0", text: signature.dump_base36(), title: "Input signature" }
+                                            } else {
+                                                span { "None" }
+                                            }
+                                            span { class: "text-neutral-500", "Status" }
+                                            span {
+                                                class: if result.signatures[i] == SignatureStatus::Valid { "text-green-400" } else { "text-red-400" },
+                                                match result.signatures[i] {
+                                                    SignatureStatus::Missing => "Unsigned",
+                                                    SignatureStatus::Valid => "Valid signature",
+                                                    SignatureStatus::Invalid => "Invalid signature",
+                                                }
+                                            }
+                                            span { class: "text-neutral-500", "Amount" }
+                                            span {
+                                                class: "font-mono",
+                                                match result.input_amounts[i] {
+                                                    Some(amount) => format!("{} SNAP", to_snap(amount)),
+                                                    None => "Unknown".to_string(),
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    h4 { "Outputs" }
+                                    for output in transaction.outputs.iter() {
+                                        div {
+                                            class: "bg-neutral-800 p-4 rounded grid grid-cols-[1fr_max-content] items-center gap-x-4 text-sm",
+                                            CopyBox { class: "w-full min-w-0", text: output.receiver.dump_base36(), title: "Receiver" }
+                                            span { class: "font-mono", "{to_snap(output.amount)} SNAP" }
+                                        }
+                                    }
+                                }
+
+                                if global().api_client.is_none() {
+                                    p { class: "text-neutral-400", "Connect to a node to compute PoW and submit." }
+                                } else {
+                                    div {
+                                        class: "flex gap-4",
+                                        if needs_pow {
+                                            button {
+                                                disabled: is_busy(),
+                                                onclick: move |_| {
+                                                    let Some(client) = global().api_client else {
+                                                        return;
+                                                    };
+                                                    let mut transaction = transaction.clone();
+                                                    let input_amounts = result.input_amounts.iter().copied().collect::<Option<Vec<u64>>>();
+                                                    spawn(async move {
+                                                        is_busy.set(true);
+                                                        status.set("Computing transaction PoW...".to_string());
+
+                                                        let result = async {
+                                                            transaction.compute_pow(&client.get_live_transaction_difficulty().await?, Some(0.2f64))?;
+                                                            inspect(transaction, input_amounts, Some(client)).await
+                                                        }
+                                                        .await;
+                                                        match result {
+                                                            Ok(result) => {
+                                                                inspection.set(Some(result));
+                                                                status.set("PoW computed".to_string());
+                                                            }
+                                                            Err(e) => status.set(e.to_string()),
+                                                        }
+
+                                                        is_busy.set(false);
+                                                    });
+                                                },
+                                                "Compute PoW"
+                                            }
+                                        }
+                                        button {
+                                            disabled: is_busy() || submitted() || !can_submit,
+                                            onclick: move |_| {
+                                                let Some(client) = global().api_client else {
+                                                    return;
+                                                };
+                                                let Some(result) = inspection() else {
+                                                    return;
+                                                };
+                                                spawn(async move {
+                                                    is_busy.set(true);
+                                                    status.set("Submitting transaction...".to_string());
+
+                                                    if let Err(e) = async {
+                                                        client.submit_transaction(result.transaction).await??;
+                                                        Ok::<(), anyhow::Error>(())
+                                                    }
+                                                    .await
+                                                    {
+                                                        status.set(e.to_string());
+                                                    } else {
+                                                        submitted.set(true);
+                                                        status.set("Transaction submitted".to_string());
+                                                    }
+
+                                                    is_busy.set(false);
+                                                });
+                                            },
+                                            "Submit"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        p { class: "text-neutral-400", "No transaction loaded" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{authorize::ask_for_auth, copy_box::CopyBox, save_wallets, GlobalContext};
+use crate::{
+    authorize::ask_for_auth, copy_box::CopyBox, save_wallets, settings::save_settings,
+    GlobalContext,
+};
 use dioxus::prelude::*;
 use snap_coin::crypto::keys::Private;
 use tokio::time::{sleep, Duration};
@@ -18,6 +21,7 @@ pub fn WalletManager() -> Element {
     let mut new_wallet_name = use_signal(|| "".to_string());
     let mut new_wallet_private = use_signal(|| "".to_string());
     let mut new_wallet_error = use_signal(|| "".to_string());
+    let mut settings_error = use_signal(|| "".to_string());
 
     // ✅ added
     let mut show_backup_popup = use_signal(|| false);
@@ -93,6 +97,7 @@ pub fn WalletManager() -> Element {
                                 let wallet_copy = wallet.clone();
                                 let wallet_copy2 = wallet.clone();
                                 let public = private.to_public();
+                                let notifications_on = global_state().settings.notifications_enabled(&public);
 
                                 rsx! {
                                     p {
@@ -156,6 +161,23 @@ pub fn WalletManager() -> Element {
 
                                         div {
                                             class: "flex items-center gap-5 justify-end w-full justify-self-end",
+                                            button {
+                                                title: "Desktop notifications for this wallet",
+                                                class: "text-sm text-nowrap".to_string() + if notifications_on { "" } else { " bg-transparent! border! border-gray-600!" },
+                                                onclick: move |_| {
+                                                    global_state.with_mut(|g| {
+                                                        if let Some(e) = &g.settings_error {
+                                                            settings_error.set(format!("Settings failed to load ({e}), changes are not saved until they do"));
+                                                            return;
+                                                        }
+                                                        g.settings.set_notifications_enabled(&public, !notifications_on);
+                                                        if let Err(e) = save_settings(&g.settings, &g.pin) {
+                                                            settings_error.set(format!("Failed to save settings: {e}"));
+                                                        }
+                                                    });
+                                                },
+                                                if notifications_on { "Notifications on" } else { "Notifications off" }
+                                            }
                                             div {
                                                 class: "",
                                                 CopyBox { title: "Wallet public key", text: public.dump_base36() }
@@ -219,6 +241,31 @@ pub fn WalletManager() -> Element {
                         }
                     }
 
+                    label {
+                        class: "flex items-center gap-2 p-5",
+                        input {
+                            r#type: "checkbox",
+                            checked: global_state().settings.notify_node_synced,
+                            onchange: move |e| {
+                                global_state.with_mut(|g| {
+                                    if let Some(load_error) = &g.settings_error {
+                                        settings_error.set(format!("Settings failed to load ({load_error}), changes are not saved until they do"));
+                                        return;
+                                    }
+                                    g.settings.notify_node_synced = e.checked();
+                                    if let Err(e) = save_settings(&g.settings, &g.pin) {
+                                        settings_error.set(format!("Failed to save settings: {e}"));
+                                    }
+                                });
+                            }
+                        }
+                        "Notify when the in-wallet node finishes syncing"
+                    }
+                    p {
+                        class: "text-red-400! font-bold",
+                        "{settings_error}"
+                    }
+
                     div {
                         class: "w-full",
 